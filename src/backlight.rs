@@ -0,0 +1,156 @@
+//! Backlight drivers.
+
+use core::convert::{Infallible, TryFrom};
+
+use embedded_hal::digital::v2::OutputPin;
+use embedded_hal::PwmPin;
+
+///
+/// A backlight with adjustable brightness.
+///
+pub trait Backlight {
+    type Error;
+
+    ///
+    /// Sets the backlight brightness.
+    ///
+    /// # Arguments
+    ///
+    /// * `percent` - brightness from 0 (off) to 100 (full brightness)
+    ///
+    fn set_brightness(&mut self, percent: u8) -> Result<(), Self::Error>;
+}
+
+///
+/// Allows the driver to borrow a backlight owned elsewhere.
+///
+impl<B> Backlight for &mut B
+where
+    B: Backlight,
+{
+    type Error = B::Error;
+
+    fn set_brightness(&mut self, percent: u8) -> Result<(), Self::Error> {
+        (**self).set_brightness(percent)
+    }
+}
+
+///
+/// Backlight switched by an output pin, any brightness above 0 turns it on.
+///
+pub struct GpioBacklight<P> {
+    pin: P,
+    active_low: bool,
+}
+
+impl<P> GpioBacklight<P>
+where
+    P: OutputPin,
+{
+    ///
+    /// Creates a backlight driven high to turn it on
+    ///
+    pub fn new(pin: P) -> Self {
+        Self {
+            pin,
+            active_low: false,
+        }
+    }
+
+    ///
+    /// Creates a backlight driven low to turn it on, e.g. through a PNP transistor
+    ///
+    pub fn new_active_low(pin: P) -> Self {
+        Self {
+            pin,
+            active_low: true,
+        }
+    }
+
+    ///
+    /// Returns the backlight pin
+    ///
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+impl<P> Backlight for GpioBacklight<P>
+where
+    P: OutputPin,
+{
+    type Error = P::Error;
+
+    fn set_brightness(&mut self, percent: u8) -> Result<(), Self::Error> {
+        if (percent > 0) != self.active_low {
+            self.pin.set_high()
+        } else {
+            self.pin.set_low()
+        }
+    }
+}
+
+///
+/// Backlight dimmed by a PWM channel, the duty cycle follows the brightness linearly.
+///
+pub struct PwmBacklight<P> {
+    pwm: P,
+    active_low: bool,
+}
+
+impl<P> PwmBacklight<P>
+where
+    P: PwmPin,
+{
+    ///
+    /// Creates a backlight and enables the PWM channel
+    ///
+    pub fn new(mut pwm: P) -> Self {
+        pwm.enable();
+        Self {
+            pwm,
+            active_low: false,
+        }
+    }
+
+    ///
+    /// Creates a backlight that is on while the output is low and enables the PWM channel
+    ///
+    pub fn new_active_low(mut pwm: P) -> Self {
+        pwm.enable();
+        Self {
+            pwm,
+            active_low: true,
+        }
+    }
+
+    ///
+    /// Disables the PWM channel and returns it
+    ///
+    pub fn release(mut self) -> P {
+        self.pwm.disable();
+        self.pwm
+    }
+}
+
+impl<P> Backlight for PwmBacklight<P>
+where
+    P: PwmPin,
+    P::Duty: Copy + Into<u32> + TryFrom<u32>,
+{
+    type Error = Infallible;
+
+    fn set_brightness(&mut self, percent: u8) -> Result<(), Self::Error> {
+        let max = self.pwm.get_max_duty();
+        let percent = if self.active_low {
+            100 - percent.min(100)
+        } else {
+            percent.min(100)
+        };
+        let duty = u64::from(max.into()) * u64::from(percent) / 100;
+        // never exceeds the max duty
+        let duty = duty as u32;
+        self.pwm.set_duty(P::Duty::try_from(duty).unwrap_or(max));
+        Ok(())
+    }
+}
@@ -1,34 +1,36 @@
 //! Original code from: https://github.com/lupyuen/piet-embedded/blob/master/piet-embedded-graphics/src/batch.rs
 //! Batch the pixels to be rendered into Pixel Rows and Pixel Blocks (contiguous Pixel Rows).
 //! This enables the pixels to be rendered efficiently as Pixel Blocks, which may be transmitted in a single Non-Blocking SPI request.
-use crate::{Error, ST7789};
+use crate::{Backlight, Error, ResetPin, ST7789};
 use display_interface::WriteOnlyDataCommand;
-use embedded_graphics_core::{
-    pixelcolor::raw::RawU16,
-    prelude::*,
-};
-use embedded_hal::digital::v2::OutputPin;
+use embedded_graphics_core::{pixelcolor::RgbColor, prelude::*};
 
-pub trait DrawBatch<DI, OUT, T, C, PinE>
+pub trait DrawBatch<DI, RST, BL, T, C, RstE, BlE>
 where
     DI: WriteOnlyDataCommand,
-    OUT: OutputPin<Error = PinE>,
-    C: PixelColor + IntoStorage + Clone, RawU16: From<C>,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
     T: IntoIterator<Item = Pixel<C>>,
 {
-    fn draw_batch(&mut self, item_pixels: T) -> Result<(), Error<PinE>>;
+    fn draw_batch(&mut self, item_pixels: T) -> Result<(), Error<RstE, BlE>>;
 }
 
-impl<DI, OUT, T, PinE, C> DrawBatch<DI, OUT, T, C, PinE> for ST7789<DI, OUT, C>
+impl<DI, RST, BL, T, C, RstE, BlE> DrawBatch<DI, RST, BL, T, C, RstE, BlE>
+    for ST7789<DI, RST, BL, C>
 where
     DI: WriteOnlyDataCommand,
-    OUT: OutputPin<Error = PinE>,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
     T: IntoIterator<Item = Pixel<C>>,
-    C: PixelColor + IntoStorage + Clone, RawU16: From<C>,
+    C: RgbColor,
 {
-    fn draw_batch(&mut self, item_pixels: T) -> Result<(), Error<PinE>> {
-        //  Get the pixels for the item to be rendered.
-        let pixels = item_pixels.into_iter();
+    fn draw_batch(&mut self, item_pixels: T) -> Result<(), Error<RstE, BlE>> {
+        //  Get the pixels for the item to be rendered, dropping the ones outside the screen.
+        let bounding_box = self.framebuffer_bounding_box();
+        let pixels = item_pixels
+            .into_iter()
+            .filter(|Pixel(point, _)| bounding_box.contains(*point));
         //  Batch the pixels into Pixel Rows.
         let rows = to_rows(pixels);
         //  Batch the Pixel Rows into Pixel Blocks.
@@ -61,17 +63,17 @@ const MAX_ROW_SIZE: usize = 50;
 /// Max number of pixels per Pixel Block
 const MAX_BLOCK_SIZE: usize = 100;
 
-/// Consecutive color words for a Pixel Row
-type RowColors = heapless::Vec<u16, MAX_ROW_SIZE>;
-/// Consecutive color words for a Pixel Block
-type BlockColors = heapless::Vec<u16, MAX_BLOCK_SIZE>;
+/// Consecutive colors for a Pixel Row
+type RowColors<C> = heapless::Vec<C, MAX_ROW_SIZE>;
+/// Consecutive colors for a Pixel Block
+type BlockColors<C> = heapless::Vec<C, MAX_BLOCK_SIZE>;
 
 /// Iterator for each Pixel Row in the pixel data. A Pixel Row consists of contiguous pixels on the same row.
 #[derive(Debug, Clone)]
 pub struct RowIterator<P, C>
 where
-P: Iterator<Item = Pixel<C>>,
-C: PixelColor + IntoStorage, RawU16: From<C>
+    P: Iterator<Item = Pixel<C>>,
+    C: RgbColor,
 {
     /// Pixels to be batched into rows
     pixels: P,
@@ -82,14 +84,14 @@ C: PixelColor + IntoStorage, RawU16: From<C>
     /// Row number
     y: u16,
     /// List of pixel colours for the entire row
-    colors: RowColors,
+    colors: RowColors<C>,
     /// True if this is the first pixel for the row
     first_pixel: bool,
 }
 
 /// Iterator for each Pixel Block in the pixel data. A Pixel Block consists of contiguous Pixel Rows with the same start and end column number.
 #[derive(Debug, Clone)]
-pub struct BlockIterator<R: Iterator<Item = PixelRow>> {
+pub struct BlockIterator<R: Iterator<Item = PixelRow<C>>, C: RgbColor> {
     /// Pixel Rows to be batched into blocks
     rows: R,
     /// Start column number
@@ -101,13 +103,13 @@ pub struct BlockIterator<R: Iterator<Item = PixelRow>> {
     /// End row number
     y_bottom: u16,
     /// List of pixel colours for the entire block, row by row
-    colors: BlockColors,
+    colors: BlockColors<C>,
     /// True if this is the first row for the block
     first_row: bool,
 }
 
 /// A row of contiguous pixels
-pub struct PixelRow {
+pub struct PixelRow<C: RgbColor> {
     /// Start column number
     pub x_left: u16,
     /// End column number
@@ -115,11 +117,11 @@ pub struct PixelRow {
     /// Row number
     pub y: u16,
     /// List of pixel colours for the entire row
-    pub colors: RowColors,
+    pub colors: RowColors<C>,
 }
 
 /// A block of contiguous pixel rows with the same start and end column number
-pub struct PixelBlock {
+pub struct PixelBlock<C: RgbColor> {
     /// Start column number
     pub x_left: u16,
     /// End column number
@@ -129,7 +131,7 @@ pub struct PixelBlock {
     /// End row number
     pub y_bottom: u16,
     /// List of pixel colours for the entire block, row by row
-    pub colors: BlockColors,
+    pub colors: BlockColors<C>,
 }
 
 /// Batch the pixels into Pixel Rows, which are contiguous pixels on the same row.
@@ -137,7 +139,7 @@ pub struct PixelBlock {
 fn to_rows<P, C>(pixels: P) -> RowIterator<P, C>
 where
     P: Iterator<Item = Pixel<C>>,
-    C: PixelColor + IntoStorage, RawU16: From<C>
+    C: RgbColor,
 {
     RowIterator::<P, C> {
         pixels,
@@ -151,12 +153,12 @@ where
 
 /// Batch the Pixel Rows into Pixel Blocks, which are contiguous Pixel Rows with the same start and end column number
 /// R can be any Pixel Row Iterator.
-fn to_blocks<R, C>(rows: R) -> BlockIterator<R>
+fn to_blocks<R, C>(rows: R) -> BlockIterator<R, C>
 where
-    R: Iterator<Item = PixelRow>,
-    C: PixelColor + IntoStorage, RawU16: From<C>
+    R: Iterator<Item = PixelRow<C>>,
+    C: RgbColor,
 {
-    BlockIterator::<R> {
+    BlockIterator::<R, C> {
         rows,
         x_left: 0,
         x_right: 0,
@@ -171,11 +173,11 @@ where
 /// P can be any Pixel Iterator (e.g. a rectangle).
 impl<P, C> Iterator for RowIterator<P, C>
 where
-P: Iterator<Item = Pixel<C>>,
-C: PixelColor + IntoStorage, RawU16: From<C>
+    P: Iterator<Item = Pixel<C>>,
+    C: RgbColor,
 {
     /// This Iterator returns Pixel Rows
-    type Item = PixelRow;
+    type Item = PixelRow<C>;
 
     /// Return the next Pixel Row of contiguous pixels on the same row
     fn next(&mut self) -> Option<Self::Item> {
@@ -204,7 +206,6 @@ C: PixelColor + IntoStorage, RawU16: From<C>
                     //  If there is a pixel...
                     let x = coord.x as u16;
                     let y = coord.y as u16;
-                    let color = RawU16::from(color).into_inner();
                     //  Save the first pixel as the row start and handle next pixel.
                     if self.first_pixel {
                         self.first_pixel = false;
@@ -212,7 +213,7 @@ C: PixelColor + IntoStorage, RawU16: From<C>
                         self.x_right = x;
                         self.y = y;
                         self.colors.clear();
-                        self.colors.push(color).expect("never");
+                        self.colors.push(color).ok().expect("never");
                         continue;
                     }
                     //  If this pixel is adjacent to the previous pixel, add to the row.
@@ -235,7 +236,7 @@ C: PixelColor + IntoStorage, RawU16: From<C>
                     self.x_right = x;
                     self.y = y;
                     self.colors.clear();
-                    self.colors.push(color).expect("never");
+                    self.colors.push(color).ok().expect("never");
                     return Some(row);
                 }
             }
@@ -245,9 +246,9 @@ C: PixelColor + IntoStorage, RawU16: From<C>
 
 /// Implement the Iterator for Pixel Blocks.
 /// R can be any Pixel Row Iterator.
-impl<R: Iterator<Item = PixelRow>> Iterator for BlockIterator<R> {
+impl<R: Iterator<Item = PixelRow<C>>, C: RgbColor> Iterator for BlockIterator<R, C> {
     /// This Iterator returns Pixel Blocks
-    type Item = PixelBlock;
+    type Item = PixelBlock<C>;
 
     /// Return the next Pixel Block of contiguous Pixel Rows with the same start and end column number
     fn next(&mut self) -> Option<Self::Item> {
@@ -0,0 +1,138 @@
+//! Display brightness and content adaptive brightness control (CABC).
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::RgbColor;
+
+use crate::instruction::Instruction;
+use crate::{Backlight, Error, ResetPin, ST7789};
+
+///
+/// Brightness control settings (WRCTRLD).
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BrightnessControl {
+    /// Enable the brightness control block (BCTRL)
+    pub enabled: bool,
+    /// Fade between brightness levels instead of switching immediately (DD)
+    pub dimming: bool,
+    /// Enable the backlight control output (BL)
+    pub backlight: bool,
+}
+
+impl BrightnessControl {
+    ///
+    /// Returns the WRCTRLD register value.
+    ///
+    pub fn to_byte(self) -> u8 {
+        (self.enabled as u8) << 5 | (self.dimming as u8) << 3 | (self.backlight as u8) << 2
+    }
+
+    ///
+    /// Decodes a WRCTRLD register value as returned by RDCTRLD.
+    ///
+    pub fn from_byte(value: u8) -> Self {
+        Self {
+            enabled: value & 0b0010_0000 != 0,
+            dimming: value & 0b0000_1000 != 0,
+            backlight: value & 0b0000_0100 != 0,
+        }
+    }
+}
+
+impl Default for BrightnessControl {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dimming: false,
+            backlight: true,
+        }
+    }
+}
+
+///
+/// Content adaptive brightness control mode (WRCACE).
+///
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CabcMode {
+    /// Adaptive brightness disabled
+    Off = 0b00,
+    /// User interface image, minimal brightness reduction
+    UserInterface = 0b01,
+    /// Still picture
+    StillPicture = 0b10,
+    /// Moving image, strongest brightness reduction
+    MovingImage = 0b11,
+}
+
+impl Default for CabcMode {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+impl CabcMode {
+    ///
+    /// Decodes a WRCACE register value as returned by RDCABC.
+    ///
+    pub fn from_byte(value: u8) -> Self {
+        match value & 0b11 {
+            0b00 => CabcMode::Off,
+            0b01 => CabcMode::UserInterface,
+            0b10 => CabcMode::StillPicture,
+            _ => CabcMode::MovingImage,
+        }
+    }
+}
+
+impl<DI, RST, BL, C, RstE, BlE> ST7789<DI, RST, BL, C>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+{
+    ///
+    /// Sets the display brightness used by the brightness control block.
+    ///
+    /// # Arguments
+    ///
+    /// * `brightness` - brightness from 0 (darkest) to 255 (brightest)
+    ///
+    pub fn set_display_brightness(&mut self, brightness: u8) -> Result<(), Error<RstE, BlE>> {
+        self.write_command(Instruction::WRDISBV)?;
+        self.write_data(&[brightness])
+    }
+
+    ///
+    /// Configures the brightness control block, brightness and CABC
+    /// only have an effect while it is enabled.
+    ///
+    pub fn set_brightness_control(
+        &mut self,
+        control: BrightnessControl,
+    ) -> Result<(), Error<RstE, BlE>> {
+        self.write_command(Instruction::WRCTRLD)?;
+        self.write_data(&[control.to_byte()])
+    }
+
+    ///
+    /// Sets the content adaptive brightness control mode.
+    ///
+    pub fn set_adaptive_brightness(&mut self, mode: CabcMode) -> Result<(), Error<RstE, BlE>> {
+        self.write_command(Instruction::WRCACE)?;
+        self.write_data(&[mode as u8])
+    }
+
+    ///
+    /// Sets the minimum brightness content adaptive brightness control may reduce to.
+    ///
+    /// # Arguments
+    ///
+    /// * `brightness` - minimum brightness from 0 (darkest) to 255 (brightest)
+    ///
+    pub fn set_cabc_min_brightness(&mut self, brightness: u8) -> Result<(), Error<RstE, BlE>> {
+        self.write_command(Instruction::WRCABCMB)?;
+        self.write_data(&[brightness])
+    }
+}
@@ -0,0 +1,142 @@
+//! Conversion of RGB colors into the interface pixel formats.
+
+use embedded_graphics_core::pixelcolor::RgbColor;
+
+// Scales a color channel from `max` to `target_max`.
+fn channel(value: u8, max: u8, target_max: u8) -> u8 {
+    if max == target_max {
+        value
+    } else {
+        ((u16::from(value) * u16::from(target_max) + u16::from(max) / 2) / u16::from(max)) as u8
+    }
+}
+
+/// Converts a color into a 12bit RGB444 word.
+pub(crate) fn to_rgb444<C: RgbColor>(color: C) -> u16 {
+    let r = channel(color.r(), C::MAX_R, 0x0F);
+    let g = channel(color.g(), C::MAX_G, 0x0F);
+    let b = channel(color.b(), C::MAX_B, 0x0F);
+
+    u16::from(r) << 8 | u16::from(g) << 4 | u16::from(b)
+}
+
+/// Converts a color into a 16bit RGB565 word.
+pub(crate) fn to_rgb565<C: RgbColor>(color: C) -> u16 {
+    let r = channel(color.r(), C::MAX_R, 0x1F);
+    let g = channel(color.g(), C::MAX_G, 0x3F);
+    let b = channel(color.b(), C::MAX_B, 0x1F);
+
+    u16::from(r) << 11 | u16::from(g) << 5 | u16::from(b)
+}
+
+/// Converts a color into the three bytes of an 18bit RGB666 pixel,
+/// each channel aligned to the upper six bits.
+pub(crate) fn to_rgb666<C: RgbColor>(color: C) -> [u8; 3] {
+    [
+        channel(color.r(), C::MAX_R, 0x3F) << 2,
+        channel(color.g(), C::MAX_G, 0x3F) << 2,
+        channel(color.b(), C::MAX_B, 0x3F) << 2,
+    ]
+}
+
+///
+/// Packs 12bit RGB444 words into bytes, two pixels per three bytes.
+/// An odd trailing pixel is sent as two bytes.
+///
+pub(crate) struct Rgb444Bytes<I> {
+    pixels: I,
+    bytes: [u8; 3],
+    pos: usize,
+    len: usize,
+}
+
+impl<I> Rgb444Bytes<I>
+where
+    I: Iterator<Item = u16>,
+{
+    pub(crate) fn new(pixels: I) -> Self {
+        Self {
+            pixels,
+            bytes: [0; 3],
+            pos: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<I> Iterator for Rgb444Bytes<I>
+where
+    I: Iterator<Item = u16>,
+{
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.pos == self.len {
+            let first = self.pixels.next()?;
+            match self.pixels.next() {
+                Some(second) => {
+                    self.bytes = [
+                        (first >> 4) as u8,
+                        ((first & 0x0F) << 4 | second >> 8) as u8,
+                        second as u8,
+                    ];
+                    self.len = 3;
+                }
+                None => {
+                    self.bytes = [(first >> 4) as u8, ((first & 0x0F) << 4) as u8, 0];
+                    self.len = 2;
+                }
+            }
+            self.pos = 0;
+        }
+
+        let byte = self.bytes[self.pos];
+        self.pos += 1;
+        Some(byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics_core::pixelcolor::{Rgb565, Rgb666, Rgb888};
+
+    #[test]
+    fn rgb444_packs_pixel_pairs() {
+        let bytes = Rgb444Bytes::new([0x123, 0x456].iter().copied());
+        assert!(bytes.eq([0x12, 0x34, 0x56].iter().copied()));
+    }
+
+    #[test]
+    fn rgb444_pads_odd_trailing_pixel() {
+        let bytes = Rgb444Bytes::new([0x123, 0x456, 0x789].iter().copied());
+        assert!(bytes.eq([0x12, 0x34, 0x56, 0x78, 0x90].iter().copied()));
+
+        let bytes = Rgb444Bytes::new([0xABC].iter().copied());
+        assert!(bytes.eq([0xAB, 0xC0].iter().copied()));
+    }
+
+    #[test]
+    fn rgb444_empty() {
+        assert_eq!(Rgb444Bytes::new(core::iter::empty()).next(), None);
+    }
+
+    #[test]
+    fn rgb444_scales_channels() {
+        assert_eq!(to_rgb444(Rgb888::new(0xFF, 0x88, 0x00)), 0xF80);
+        assert_eq!(to_rgb444(Rgb565::new(0x1F, 0x00, 0x1F)), 0xF0F);
+    }
+
+    #[test]
+    fn rgb565_passes_through() {
+        assert_eq!(to_rgb565(Rgb565::new(0x1F, 0x2A, 0x01)), 0xFD41);
+        assert_eq!(to_rgb565(Rgb888::new(0xFF, 0xFF, 0xFF)), 0xFFFF);
+    }
+
+    #[test]
+    fn rgb666_aligns_to_upper_bits() {
+        assert_eq!(to_rgb666(Rgb666::new(1, 2, 3)), [0x04, 0x08, 0x0C]);
+        assert_eq!(to_rgb666(Rgb565::new(0x1F, 0x3F, 0x00)), [0xFC, 0xFC, 0x00]);
+        assert_eq!(to_rgb666(Rgb888::new(0x80, 0xFF, 0x00)), [0x80, 0xFC, 0x00]);
+    }
+}
@@ -0,0 +1,99 @@
+//! Display initialization configuration.
+
+use crate::{
+    ColorOrder, FrameRate, GammaCurve, GammaPreset, Orientation, PanelPowerConfig, PixelFormat,
+    ScrollArea,
+};
+
+///
+/// A single entry of an init command table as shipped by panel vendors.
+///
+/// ```
+/// use st7789::InitCommand;
+///
+/// const PANEL_INIT: &[InitCommand] = &[
+///     InitCommand::new(0xB2, &[0x0C, 0x0C, 0x00, 0x33, 0x33], 0), // PORCTRL
+///     InitCommand::new(0xB7, &[0x35], 0),                         // GCTRL
+///     InitCommand::new(0xBB, &[0x19], 0),                         // VCOMS
+///     InitCommand::new(0x11, &[], 120_000),                       // SLPOUT
+/// ];
+/// ```
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InitCommand {
+    /// Command byte
+    pub command: u8,
+    /// Data bytes sent after the command
+    pub data: &'static [u8],
+    /// Delay after the command in microseconds
+    pub delay_us: u32,
+}
+
+impl InitCommand {
+    ///
+    /// Creates an init table entry
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - command byte
+    /// * `data` - data bytes sent after the command
+    /// * `delay_us` - delay after the command in microseconds
+    ///
+    pub const fn new(command: u8, data: &'static [u8], delay_us: u32) -> Self {
+        Self {
+            command,
+            data,
+            delay_us,
+        }
+    }
+}
+
+///
+/// Settings applied by `ST7789::init_with`.
+///
+/// The default reproduces the sequence `ST7789::init` uses: inversion and
+/// color order of the panel, 16bit pixels and portrait orientation.
+///
+#[derive(Copy, Clone, Debug)]
+pub struct InitConfig {
+    /// Turn on display inversion (INVON), `None` uses `Panel::inverted`
+    pub inverted: Option<bool>,
+    /// Sub-pixel color order written to MADCTL, `None` uses `Panel::color_order`
+    pub color_order: Option<ColorOrder>,
+    /// Interface pixel format written to COLMOD
+    pub pixel_format: PixelFormat,
+    /// Initial display orientation
+    pub orientation: Orientation,
+    /// Vertical scroll area, `None` scrolls the visible rows of the panel
+    pub scroll_area: Option<ScrollArea>,
+    /// Predefined gamma curve, `None` keeps the controller default
+    pub gamma_preset: Option<GammaPreset>,
+    /// Custom gamma tables, `None` keeps the controller default
+    pub gamma_curve: Option<GammaCurve>,
+    /// Power and porch settings, `None` keeps the controller default
+    pub power: Option<PanelPowerConfig>,
+    /// Frame rate in normal mode, `None` keeps the controller default
+    pub frame_rate: Option<FrameRate>,
+    /// Vendor init table executed before the display is turned on, overrides the settings above
+    pub vendor_commands: &'static [InitCommand],
+    /// Turn the backlight off for 10ms during reset and restore its brightness, `false` leaves it untouched
+    pub blink_backlight: bool,
+}
+
+impl Default for InitConfig {
+    fn default() -> Self {
+        Self {
+            inverted: None,
+            color_order: None,
+            pixel_format: PixelFormat::Rgb565,
+            orientation: Orientation::Portrait,
+            scroll_area: None,
+            gamma_preset: None,
+            gamma_curve: None,
+            power: None,
+            frame_rate: None,
+            vendor_commands: &[],
+            blink_backlight: true,
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Gamma curve configuration (GAMSET, PVGAMCTRL and NVGAMCTRL).
+
+///
+/// Predefined gamma curves selected with GAMSET.
+///
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GammaPreset {
+    /// Gamma 2.2 (reset default)
+    G2_2 = 0x01,
+    /// Gamma 1.8
+    G1_8 = 0x02,
+    /// Gamma 2.5
+    G2_5 = 0x04,
+    /// Gamma 1.0
+    G1_0 = 0x08,
+}
+
+impl Default for GammaPreset {
+    fn default() -> Self {
+        Self::G2_2
+    }
+}
+
+///
+/// Voltage gamma table as written to PVGAMCTRL or NVGAMCTRL.
+///
+/// Field names follow the datasheet, values wider than the register field are truncated.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GammaTable {
+    /// V0, 4 bits
+    pub v0: u8,
+    /// V1, 6 bits
+    pub v1: u8,
+    /// V2, 6 bits
+    pub v2: u8,
+    /// V4, 5 bits
+    pub v4: u8,
+    /// V6, 5 bits
+    pub v6: u8,
+    /// V13, 4 bits
+    pub v13: u8,
+    /// V20, 7 bits
+    pub v20: u8,
+    /// V27, 3 bits
+    pub v27: u8,
+    /// V36, 3 bits
+    pub v36: u8,
+    /// V43, 7 bits
+    pub v43: u8,
+    /// V50, 4 bits
+    pub v50: u8,
+    /// V57, 5 bits
+    pub v57: u8,
+    /// V59, 5 bits
+    pub v59: u8,
+    /// V61, 6 bits
+    pub v61: u8,
+    /// V62, 6 bits
+    pub v62: u8,
+    /// V63, 4 bits
+    pub v63: u8,
+    /// J0, 2 bits
+    pub j0: u8,
+    /// J1, 2 bits
+    pub j1: u8,
+}
+
+impl GammaTable {
+    ///
+    /// Encodes the table into the 14 parameter bytes of PVGAMCTRL/NVGAMCTRL
+    ///
+    pub fn to_bytes(&self) -> [u8; 14] {
+        [
+            (self.v63 & 0x0F) << 4 | (self.v0 & 0x0F),
+            self.v1 & 0x3F,
+            self.v2 & 0x3F,
+            self.v4 & 0x1F,
+            self.v6 & 0x1F,
+            (self.j0 & 0x03) << 4 | (self.v13 & 0x0F),
+            self.v20 & 0x7F,
+            (self.v36 & 0x07) << 4 | (self.v27 & 0x07),
+            self.v43 & 0x7F,
+            (self.j1 & 0x03) << 4 | (self.v50 & 0x0F),
+            self.v57 & 0x1F,
+            self.v59 & 0x1F,
+            self.v61 & 0x3F,
+            self.v62 & 0x3F,
+        ]
+    }
+}
+
+///
+/// Positive and negative voltage gamma tables.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GammaCurve {
+    /// Positive voltage gamma (PVGAMCTRL)
+    pub positive: GammaTable,
+    /// Negative voltage gamma (NVGAMCTRL)
+    pub negative: GammaTable,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(value: u8) -> GammaTable {
+        GammaTable {
+            v0: value,
+            v1: value,
+            v2: value,
+            v4: value,
+            v6: value,
+            v13: value,
+            v20: value,
+            v27: value,
+            v36: value,
+            v43: value,
+            v50: value,
+            v57: value,
+            v59: value,
+            v61: value,
+            v62: value,
+            v63: value,
+            j0: value,
+            j1: value,
+        }
+    }
+
+    #[test]
+    fn vendor_table_round_trips() {
+        // positive gamma of the common 240x240 module init sequence
+        let positive = GammaTable {
+            v0: 0x0,
+            v1: 0x00,
+            v2: 0x05,
+            v4: 0x0E,
+            v6: 0x15,
+            v13: 0xD,
+            v20: 0x37,
+            v27: 0x3,
+            v36: 0x4,
+            v43: 0x47,
+            v50: 0x9,
+            v57: 0x15,
+            v59: 0x12,
+            v61: 0x16,
+            v62: 0x19,
+            v63: 0xD,
+            j0: 0x0,
+            j1: 0x0,
+        };
+
+        assert_eq!(
+            positive.to_bytes(),
+            [0xD0, 0x00, 0x05, 0x0E, 0x15, 0x0D, 0x37, 0x43, 0x47, 0x09, 0x15, 0x12, 0x16, 0x19]
+        );
+    }
+
+    #[test]
+    fn out_of_range_values_are_masked() {
+        assert_eq!(
+            table(0xFF).to_bytes(),
+            [0xFF, 0x3F, 0x3F, 0x1F, 0x1F, 0x3F, 0x7F, 0x77, 0x7F, 0x3F, 0x1F, 0x1F, 0x3F, 0x3F]
+        );
+        assert_eq!(table(0x80).to_bytes(), [0x00; 14]);
+    }
+}
@@ -1,39 +1,67 @@
-use embedded_graphics_core::prelude::{DrawTarget, IntoStorage, Point, Size, PixelColor};
-use embedded_graphics_core::{
-    pixelcolor::raw::{RawData, RawU16},
-    primitives::Rectangle,
-};
+use embedded_graphics_core::pixelcolor::RgbColor;
+use embedded_graphics_core::prelude::{DrawTarget, Point, Size};
+use embedded_graphics_core::primitives::{PointsIter, Rectangle};
 use embedded_graphics_core::{prelude::OriginDimensions, Pixel};
 
-use embedded_hal::digital::v2::OutputPin;
-
-use crate::{Error, Orientation, ST7789};
+use crate::{Backlight, Error, ResetPin, ST7789};
 use display_interface::WriteOnlyDataCommand;
 
-impl<DI, OUT, PinE, C> ST7789<DI, OUT, C>
+impl<DI, RST, BL, C, RstE, BlE> ST7789<DI, RST, BL, C>
 where
     DI: WriteOnlyDataCommand,
-    OUT: OutputPin<Error = PinE>,
-    C: PixelColor + IntoStorage + Clone, RawU16: From<C>,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
 {
-    /// Returns the bounding box for the entire framebuffer.
-    fn framebuffer_bounding_box(&self) -> Rectangle {
-        let size = match self.orientation {
-            Orientation::Portrait | Orientation::PortraitSwapped => Size::new(240, 320),
-            Orientation::Landscape | Orientation::LandscapeSwapped => Size::new(320, 240),
-        };
+    /// Returns the bounding box for the visible part of the framebuffer.
+    pub(crate) fn framebuffer_bounding_box(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), self.size())
+    }
 
-        Rectangle::new(Point::zero(), size)
+    /// Fills `area` row by row with `colors`, drawing only the pixels inside `clip`.
+    pub(crate) fn fill_contiguous_clipped<I>(
+        &mut self,
+        area: &Rectangle,
+        clip: &Rectangle,
+        colors: I,
+    ) -> Result<(), Error<RstE, BlE>>
+    where
+        I: IntoIterator<Item = C>,
+    {
+        let drawn = area
+            .intersection(clip)
+            .intersection(&self.framebuffer_bounding_box());
+
+        if let Some(bottom_right) = drawn.bottom_right() {
+            let count = (drawn.size.width * drawn.size.height) as usize;
+            // skip the colors of clipped pixels
+            let mut colors = area
+                .points()
+                .zip(colors)
+                .filter(|(point, _)| drawn.contains(*point))
+                .map(|(_, color)| color)
+                .take(count);
+
+            let sx = drawn.top_left.x as u16;
+            let sy = drawn.top_left.y as u16;
+            let ex = bottom_right.x as u16;
+            let ey = bottom_right.y as u16;
+            self.set_pixels(sx, sy, ex, ey, &mut colors)
+        } else {
+            // nothing to draw
+            Ok(())
+        }
     }
 }
 
-impl<DI, OUT, PinE, C> DrawTarget for ST7789<DI, OUT, C>
+impl<DI, RST, BL, C, RstE, BlE> DrawTarget for ST7789<DI, RST, BL, C>
 where
     DI: WriteOnlyDataCommand,
-    OUT: OutputPin<Error = PinE>,
-    C: PixelColor + IntoStorage + Clone, RawU16: From<C>,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
 {
-    type Error = Error<PinE>;
+    type Error = Error<RstE, BlE>;
     type Color = C;
 
     #[cfg(not(feature = "batch"))]
@@ -41,12 +69,12 @@ where
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        for pixel in pixels {
-            let color = RawU16::from(pixel.1).into_inner();
-            let x = pixel.0.x as u16;
-            let y = pixel.0.y as u16;
+        let bounding_box = self.framebuffer_bounding_box();
 
-            self.set_pixel(x, y, color)?;
+        for Pixel(point, color) in pixels {
+            if bounding_box.contains(point) {
+                self.set_pixel(point.x as u16, point.y as u16, color)?;
+            }
         }
 
         Ok(())
@@ -66,27 +94,8 @@ where
     where
         I: IntoIterator<Item = Self::Color>,
     {
-        if let Some(bottom_right) = area.bottom_right() {
-            let mut count = 0u32;
-            let max = area.size.width * area.size.height;
-
-            let mut colors = colors
-                .into_iter()
-                .take_while(|_| {
-                    count += 1;
-                    count <= max
-                })
-                .map(|color| RawU16::from(color).into_inner());
-
-            let sx = area.top_left.x as u16;
-            let sy = area.top_left.y as u16;
-            let ex = bottom_right.x as u16;
-            let ey = bottom_right.y as u16;
-            self.set_pixels(sx, sy, ex, ey, &mut colors)
-        } else {
-            // nothing to draw
-            Ok(())
-        }
+        let bounding_box = self.framebuffer_bounding_box();
+        self.fill_contiguous_clipped(area, &bounding_box, colors)
     }
 
     fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
@@ -96,7 +105,7 @@ where
             let mut count = 0u32;
             let max = area.size.width * area.size.height;
 
-            let mut colors = core::iter::repeat(RawU16::from(color).into_inner()).take_while(|_| {
+            let mut colors = core::iter::repeat(color).take_while(|_| {
                 count += 1;
                 count <= max
             });
@@ -116,26 +125,146 @@ where
     where
         Self: Sized,
     {
-        let colors = core::iter::repeat(RawU16::from(color).into_inner()).take(240 * 320); // blank entire HW RAM contents
+        let (ram_x, ram_y) = self.panel.ram_size(self.madctl);
+        let count = u32::from(ram_x) * u32::from(ram_y);
+        // blank entire HW RAM contents
+        let colors = (0..count).map(|_| color);
 
-        match self.orientation {
-            Orientation::Portrait | Orientation::PortraitSwapped => {
-                self.set_pixels(0, 0, 239, 319, colors)
-            }
-            Orientation::Landscape | Orientation::LandscapeSwapped => {
-                self.set_pixels(0, 0, 319, 239, colors)
-            }
-        }
+        self.set_ram_pixels(0, 0, ram_x - 1, ram_y - 1, colors)
     }
 }
 
-impl<DI, OUT, PinE, C> OriginDimensions for ST7789<DI, OUT, C>
+impl<DI, RST, BL, C, RstE, BlE> OriginDimensions for ST7789<DI, RST, BL, C>
 where
     DI: WriteOnlyDataCommand,
-    OUT: OutputPin<Error = PinE>,
-    C: PixelColor + IntoStorage + Clone, RawU16: From<C>,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
 {
     fn size(&self) -> Size {
-        Size::new(self.size_x.into(), self.size_y.into()) // visible area, not RAM-pixel size
+        let (size_x, size_y) = self.panel.size(self.madctl);
+        Size::new(size_x.into(), size_y.into()) // visible area, not RAM-pixel size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::instruction::Instruction;
+    use crate::mock::{display, Transfer};
+    use crate::Panel;
+    use embedded_graphics_core::pixelcolor::raw::{RawData, RawU16};
+    use embedded_graphics_core::pixelcolor::Rgb565;
+
+    fn color(index: u8) -> Rgb565 {
+        Rgb565::new(index, 0, 0)
+    }
+
+    // Transfers writing the given colors to a RAM window.
+    fn transfers(sx: u16, sy: u16, ex: u16, ey: u16, colors: &[u8]) -> Vec<Transfer> {
+        let window = |start: u16, end: u16| {
+            Transfer::Data(
+                start
+                    .to_be_bytes()
+                    .iter()
+                    .chain(&end.to_be_bytes())
+                    .copied()
+                    .collect(),
+            )
+        };
+        let data = colors
+            .iter()
+            .flat_map(|&index| RawU16::from(color(index)).into_inner().to_be_bytes())
+            .collect();
+
+        [
+            Transfer::Command(Instruction::CASET as u8),
+            window(sx, ex),
+            Transfer::Command(Instruction::RASET as u8),
+            window(sy, ey),
+            Transfer::Command(Instruction::RAMWR as u8),
+            Transfer::Data(data),
+        ]
+        .into()
+    }
+
+    // Fills a 3x3 area at the given position of a 4x4 panel with colors 0 to 8.
+    fn fill(x: i32, y: i32) -> Vec<Transfer> {
+        let mut display = display(Panel::new(4, 4));
+        let area = Rectangle::new(Point::new(x, y), Size::new(3, 3));
+        display.fill_contiguous(&area, (0..9).map(color)).unwrap();
+
+        display.di.transfers
+    }
+
+    #[test]
+    fn fill_contiguous_clips_left() {
+        assert_eq!(fill(-1, 1), transfers(0, 1, 1, 3, &[1, 2, 4, 5, 7, 8]));
+    }
+
+    #[test]
+    fn fill_contiguous_clips_top() {
+        assert_eq!(fill(1, -1), transfers(1, 0, 3, 1, &[3, 4, 5, 6, 7, 8]));
+    }
+
+    #[test]
+    fn fill_contiguous_clips_right() {
+        assert_eq!(fill(2, 1), transfers(2, 1, 3, 3, &[0, 1, 3, 4, 6, 7]));
+    }
+
+    #[test]
+    fn fill_contiguous_clips_bottom() {
+        assert_eq!(fill(1, 2), transfers(1, 2, 3, 3, &[0, 1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn fill_contiguous_off_screen() {
+        assert!(fill(-3, 0).is_empty());
+        assert!(fill(4, 4).is_empty());
+    }
+
+    #[test]
+    fn fill_contiguous_clipped_to_window() {
+        let mut display = display(Panel::new(4, 4));
+        let area = Rectangle::new(Point::new(-1, -1), Size::new(3, 3));
+        let clip = Rectangle::new(Point::new(0, 0), Size::new(1, 2));
+        display
+            .fill_contiguous_clipped(&area, &clip, (0..9).map(color))
+            .unwrap();
+
+        assert_eq!(display.di.transfers, transfers(0, 0, 0, 1, &[4, 7]));
+    }
+
+    fn draw_off_screen() -> Vec<Transfer> {
+        let mut display = display(Panel::new(4, 4));
+        let pixels = [
+            Pixel(Point::new(-1, 0), color(1)),
+            Pixel(Point::new(0, -1), color(2)),
+            Pixel(Point::new(0, 0), color(3)),
+            Pixel(Point::new(1, 0), color(4)),
+            Pixel(Point::new(4, 0), color(5)),
+        ];
+        display.draw_iter(pixels.iter().copied()).unwrap();
+
+        display.di.transfers
+    }
+
+    #[cfg(feature = "batch")]
+    #[test]
+    fn draw_iter_skips_negative_coordinates() {
+        assert_eq!(draw_off_screen(), transfers(0, 0, 1, 0, &[3, 4]));
+    }
+
+    #[cfg(not(feature = "batch"))]
+    #[test]
+    fn draw_iter_skips_negative_coordinates() {
+        let mut expected = transfers(0, 0, 0, 0, &[3]);
+        expected.extend(transfers(1, 0, 1, 0, &[4]));
+
+        assert_eq!(draw_off_screen(), expected);
     }
 }
@@ -1,16 +1,25 @@
 /// ST7789 instructions.
 #[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Instruction {
+    // System function commands
     NOP = 0x00,
     SWRESET = 0x01,
     RDDID = 0x04,
     RDDST = 0x09,
+    RDDPM = 0x0A,
+    RDDMADCTL = 0x0B,
+    RDDCOLMOD = 0x0C,
+    RDDIM = 0x0D,
+    RDDSM = 0x0E,
+    RDDSDR = 0x0F,
     SLPIN = 0x10,
     SLPOUT = 0x11,
     PTLON = 0x12,
     NORON = 0x13,
     INVOFF = 0x20,
     INVON = 0x21,
+    GAMSET = 0x26,
     DISPOFF = 0x28,
     DISPON = 0x29,
     CASET = 0x2A,
@@ -23,6 +32,58 @@ pub enum Instruction {
     TEON = 0x35,
     MADCTL = 0x36,
     VSCAD = 0x37,
+    IDMOFF = 0x38,
+    IDMON = 0x39,
     COLMOD = 0x3A,
+    RAMWRC = 0x3C,
+    RAMRDC = 0x3E,
+    TESCAN = 0x44,
+    RDTESCAN = 0x45,
+    WRDISBV = 0x51,
+    RDDISBV = 0x52,
+    WRCTRLD = 0x53,
+    RDCTRLD = 0x54,
+    WRCACE = 0x55,
+    RDCABC = 0x56,
+    WRCABCMB = 0x5E,
+    RDCABCMB = 0x5F,
+    RDABCSDR = 0x68,
+    RDID1 = 0xDA,
+    RDID2 = 0xDB,
+    RDID3 = 0xDC,
+
+    // Panel function commands
+    RAMCTRL = 0xB0,
+    RGBCTRL = 0xB1,
+    PORCTRL = 0xB2,
+    FRCTRL1 = 0xB3,
+    PARCTRL = 0xB5,
+    GCTRL = 0xB7,
+    GTADJ = 0xB8,
+    DGMEN = 0xBA,
+    VCOMS = 0xBB,
+    POWSAVE = 0xBC,
+    DLPOFFSAVE = 0xBD,
+    LCMCTRL = 0xC0,
+    IDSET = 0xC1,
+    VDVVRHEN = 0xC2,
+    VRHS = 0xC3,
+    VDVS = 0xC4,
     VCMOFSET = 0xC5,
+    FRCTRL2 = 0xC6,
+    CABCCTRL = 0xC7,
+    REGSEL1 = 0xC8,
+    REGSEL2 = 0xCA,
+    PWMFRSEL = 0xCC,
+    PWCTRL1 = 0xD0,
+    VAPVANEN = 0xD2,
+    CMD2EN = 0xDF,
+    PVGAMCTRL = 0xE0,
+    NVGAMCTRL = 0xE1,
+    DGMLUTR = 0xE2,
+    DGMLUTB = 0xE3,
+    GATECTRL = 0xE4,
+    SPI2EN = 0xE7,
+    PWCTRL2 = 0xE8,
+    EQCTRL = 0xE9,
 }
@@ -4,46 +4,98 @@
 
 //! This crate provides a ST7789 driver to connect to TFT displays.
 
+mod backlight;
+mod brightness;
+mod color;
+mod config;
+mod font;
+mod gamma;
 pub mod instruction;
+mod madctl;
+mod mode;
+mod panel;
+mod power;
+mod read;
+mod scroll;
+mod terminal;
+pub mod typestate;
 
+pub use crate::backlight::{Backlight, GpioBacklight, PwmBacklight};
+pub use crate::brightness::{BrightnessControl, CabcMode};
+use crate::color::Rgb444Bytes;
+pub use crate::config::{InitCommand, InitConfig};
+pub use crate::gamma::{GammaCurve, GammaPreset, GammaTable};
 use crate::instruction::Instruction;
+pub use crate::madctl::MemoryAccessControl;
+pub use crate::mode::PowerState;
+pub use crate::panel::Panel;
+pub use crate::power::{FrameRate, InversionMode, PanelPowerConfig, PorchConfig};
+pub use crate::read::{
+    DisplayId, DisplayImageMode, DisplayPowerMode, DisplaySignalMode, DisplayStatus, ReadColor,
+    ReadDataCommand, SelfDiagnostic,
+};
+pub use crate::scroll::{ScrollArea, ScrollAxis};
+pub use crate::terminal::Terminal;
+use core::convert::Infallible;
 use core::iter::once;
 use core::marker::PhantomData;
 
 use display_interface::DataFormat::{U16BEIter, U8Iter};
 use display_interface::WriteOnlyDataCommand;
-use embedded_graphics_core::pixelcolor::raw::RawU16;
-use embedded_graphics_core::prelude::{PixelColor, IntoStorage};
+use embedded_graphics_core::pixelcolor::RgbColor;
 use embedded_hal::blocking::delay::DelayUs;
 use embedded_hal::digital::v2::OutputPin;
 
 #[cfg(feature = "graphics")]
 mod graphics;
+#[cfg(feature = "graphics")]
+mod window;
+#[cfg(feature = "graphics")]
+pub use crate::window::Window;
 
 #[cfg(feature = "batch")]
 mod batch;
 
+#[cfg(test)]
+mod mock;
+
 ///
 /// ST7789 driver to connect to TFT displays.
-/// Support Rgb565 and Bgr565
+/// Supports any RGB color type, colors are converted to the interface pixel format
 ///
-pub struct ST7789<DI, OUT, C>
+pub struct ST7789<DI, RST, BL, C>
 where
     DI: WriteOnlyDataCommand,
-    OUT: OutputPin,
-    C: PixelColor + IntoStorage + Clone, RawU16: From<C>,
+    RST: ResetPin,
+    BL: Backlight,
+    C: RgbColor,
 {
     // Display interface
     di: DI,
-    // Reset pin.
-    rst: Option<OUT>,
-    // Backlight pin,
-    bl: Option<OUT>,
-    // Visible size (x, y)
-    size_x: u16,
-    size_y: u16,
-    // Current orientation
-    orientation: Orientation,
+    // Reset pin
+    rst: RST,
+    // Backlight
+    bl: BL,
+    // Backlight brightness in percent
+    brightness: u8,
+    // Panel geometry
+    panel: Panel,
+    // Current memory access control
+    madctl: MemoryAccessControl,
+    // Interface pixel format
+    pixel_format: PixelFormat,
+    // Vertical scroll area
+    scroll_area: ScrollArea,
+    // Scroll offset relative to the scroll area
+    scroll_offset: u16,
+    // Partial display mode active
+    partial_mode: bool,
+    // Idle display mode active
+    idle_mode: bool,
+    // Sleep mode active
+    sleeping: bool,
+    // Display output turned on
+    display_on: bool,
 
     _phantom: PhantomData<C>,
 }
@@ -52,7 +104,7 @@ where
 /// Display orientation.
 ///
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Orientation {
     Portrait = 0b0000_0000,         // no inverting
     Landscape = 0b0110_0000,        // invert column and page/column order
@@ -66,6 +118,54 @@ impl Default for Orientation {
     }
 }
 
+///
+/// Sub-pixel color order of the panel.
+///
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ColorOrder {
+    Rgb = 0b0000_0000,
+    Bgr = 0b0000_1000,
+}
+
+impl Default for ColorOrder {
+    fn default() -> Self {
+        Self::Rgb
+    }
+}
+
+///
+/// Interface pixel format (COLMOD).
+///
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// 12bit 4k colors, 1.5 bytes per pixel
+    Rgb444 = 0b0101_0011,
+    /// 16bit 65k colors, 2 bytes per pixel
+    Rgb565 = 0b0101_0101,
+    /// 18bit 262k colors, 3 bytes per pixel
+    Rgb666 = 0b0110_0110,
+}
+
+impl Default for PixelFormat {
+    fn default() -> Self {
+        Self::Rgb565
+    }
+}
+
+impl PixelFormat {
+    // Decodes the interface format bits of a COLMOD value.
+    pub(crate) fn from_byte(value: u8) -> Option<Self> {
+        match value & 0b111 {
+            0b011 => Some(Self::Rgb444),
+            0b101 => Some(Self::Rgb565),
+            0b110 => Some(Self::Rgb666),
+            _ => None,
+        }
+    }
+}
+
 ///
 /// Tearing effect output setting.
 ///
@@ -85,20 +185,152 @@ pub enum BacklightState {
     Off,
 }
 
+///
+/// Display reset pin, implemented for every output pin and `NoPin`.
+///
+pub trait ResetPin {
+    type Error;
+
+    /// Drives the pin low, resetting the display
+    fn assert_reset(&mut self) -> Result<(), Self::Error>;
+
+    /// Drives the pin high, releasing the display from reset
+    fn release_reset(&mut self) -> Result<(), Self::Error>;
+
+    /// Returns false if no pin is connected and toggling it has no effect
+    fn is_connected(&self) -> bool {
+        true
+    }
+}
+
+impl<P> ResetPin for P
+where
+    P: OutputPin,
+{
+    type Error = P::Error;
+
+    fn assert_reset(&mut self) -> Result<(), Self::Error> {
+        self.set_low()
+    }
+
+    fn release_reset(&mut self) -> Result<(), Self::Error> {
+        self.set_high()
+    }
+}
+
+///
+/// A driver that `Terminal` and `Window` can borrow: `ST7789` itself or a
+/// `typestate::Driver` in the `Ready` state.
+///
+/// This trait is sealed and can't be implemented outside of this crate.
+///
+pub trait DisplayDriver<DI, RST, BL, C>: private::Borrow<DI, RST, BL, C>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin,
+    BL: Backlight,
+    C: RgbColor,
+{
+}
+
+impl<T, DI, RST, BL, C> DisplayDriver<DI, RST, BL, C> for T
+where
+    T: private::Borrow<DI, RST, BL, C>,
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin,
+    BL: Backlight,
+    C: RgbColor,
+{
+}
+
+impl<DI, RST, BL, C> private::Borrow<DI, RST, BL, C> for ST7789<DI, RST, BL, C>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin,
+    BL: Backlight,
+    C: RgbColor,
+{
+    fn driver(&mut self, _token: private::Token) -> &mut ST7789<DI, RST, BL, C> {
+        self
+    }
+}
+
+pub(crate) mod private {
+    use crate::{Backlight, ResetPin, ST7789};
+    use display_interface::WriteOnlyDataCommand;
+    use embedded_graphics_core::pixelcolor::RgbColor;
+
+    // Gives borrowing helpers access to the driver, the token keeps users from calling it.
+    pub trait Borrow<DI, RST, BL, C>
+    where
+        DI: WriteOnlyDataCommand,
+        RST: ResetPin,
+        BL: Backlight,
+        C: RgbColor,
+    {
+        fn driver(&mut self, token: Token) -> &mut ST7789<DI, RST, BL, C>;
+    }
+
+    // Can only be created inside this crate.
+    pub struct Token(pub(crate) ());
+}
+
+///
+/// Placeholder for an unconnected reset pin or backlight.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct NoPin;
+
+impl ResetPin for NoPin {
+    type Error = Infallible;
+
+    fn assert_reset(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn release_reset(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        false
+    }
+}
+
+impl Backlight for NoPin {
+    type Error = Infallible;
+
+    fn set_brightness(&mut self, _percent: u8) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
 ///
 /// An error holding its source (pins or SPI)
 ///
 #[derive(Debug)]
-pub enum Error<PinE> {
+pub enum Error<RstE, BlE = RstE> {
     DisplayError,
-    Pin(PinE),
+    /// The reset pin failed to change its level
+    Pin(RstE),
+    /// The backlight failed to change its brightness
+    Backlight(BlE),
+    /// A parameter was outside of its valid range
+    InvalidArgument,
+    /// The requested power transition is not possible from the contained state
+    InvalidPowerState(PowerState),
+    /// Reads returned only 0x00 or 0xFF, no display is responding
+    BusFloating,
+    /// The display responded with an ID that doesn't match the expected ID
+    UnexpectedId(DisplayId),
 }
 
-impl<DI, OUT, PinE, C> ST7789<DI, OUT, C>
+impl<DI, RST, BL, C, RstE, BlE> ST7789<DI, RST, BL, C>
 where
     DI: WriteOnlyDataCommand,
-    OUT: OutputPin<Error = PinE>,
-    C: PixelColor + IntoStorage + Clone, RawU16: From<C>,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
 {
     ///
     /// Creates a new ST7789 driver instance
@@ -106,75 +338,170 @@ where
     /// # Arguments
     ///
     /// * `di` - a display interface for talking with the display
-    /// * `rst` - display hard reset pin
-    /// * `bl` - backlight pin
-    /// * `size_x` - x axis resolution of the display in pixels
-    /// * `size_y` - y axis resolution of the display in pixels
+    /// * `rst` - display hard reset pin, `NoPin` if not connected
+    /// * `bl` - backlight, e.g. a `GpioBacklight` or `PwmBacklight`, `NoPin` if not connected
+    /// * `panel` - geometry of the attached panel
     ///
-    pub fn new(di: DI, rst: Option<OUT>, bl: Option<OUT>, size_x: u16, size_y: u16) -> Self {
+    pub fn new(di: DI, rst: RST, bl: BL, panel: Panel) -> Self {
         Self {
             di,
             rst,
             bl,
-            size_x,
-            size_y,
-            orientation: Orientation::default(),
-            _phantom: PhantomData::default(),
+            brightness: 100,
+            panel,
+            madctl: MemoryAccessControl::default().with_color_order(panel.color_order),
+            pixel_format: PixelFormat::default(),
+            scroll_area: ScrollArea::for_panel(&panel),
+            scroll_offset: 0,
+            partial_mode: false,
+            idle_mode: false,
+            sleeping: true,
+            display_on: false,
+            _phantom: PhantomData,
         }
     }
 
     ///
-    /// Runs commands to initialize the display
+    /// Runs commands to initialize the display using the settings of the panel
+    ///
+    /// # Arguments
+    ///
+    /// * `delay_source` - mutable reference to a delay provider
+    ///
+    pub fn init(&mut self, delay_source: &mut impl DelayUs<u32>) -> Result<(), Error<RstE, BlE>> {
+        let config = InitConfig {
+            orientation: self.orientation().unwrap_or_default(),
+            ..InitConfig::default()
+        };
+
+        self.init_with(&config, delay_source)
+    }
+
+    ///
+    /// Runs commands to initialize the display with the given configuration.
+    /// Returns `Error::InvalidArgument` if the panel doesn't fit the controller RAM
+    /// (see `Panel::is_valid`) or the scroll area doesn't cover it.
+    ///
+    /// The vendor commands run after the other settings and override them,
+    /// see `run_init_table` for the state the driver keeps track of.
     ///
     /// # Arguments
     ///
+    /// * `config` - initialization settings
     /// * `delay_source` - mutable reference to a delay provider
     ///
-    pub fn init(&mut self, delay_source: &mut impl DelayUs<u32>) -> Result<(), Error<PinE>> {
+    pub fn init_with(
+        &mut self,
+        config: &InitConfig,
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Result<(), Error<RstE, BlE>> {
+        let scroll_area = config
+            .scroll_area
+            .unwrap_or_else(|| ScrollArea::for_panel(&self.panel));
+        if !self.panel.is_valid() || !scroll_area.is_valid(&self.panel) {
+            return Err(Error::InvalidArgument);
+        }
+
         self.hard_reset(delay_source)?;
-        if let Some(bl) = self.bl.as_mut() {
-            bl.set_low().map_err(Error::Pin)?;
+        if config.blink_backlight {
+            let brightness = self.brightness;
+            self.set_brightness(0)?;
             delay_source.delay_us(10_000);
-            bl.set_high().map_err(Error::Pin)?;
+            self.set_brightness(brightness)?;
         }
 
         self.write_command(Instruction::SWRESET)?; // reset display
+        self.reset_state();
         delay_source.delay_us(150_000);
         self.write_command(Instruction::SLPOUT)?; // turn off sleep
-        delay_source.delay_us(10_000);
+        self.sleeping = false;
+        delay_source.delay_us(mode::SLEEP_TRANSITION_US); // SLPIN may only follow after 120ms
         self.write_command(Instruction::INVOFF)?; // turn off invert
-        self.write_command(Instruction::VSCRDER)?; // vertical scroll definition
-        self.write_data(&[0u8, 0u8, 0x14u8, 0u8, 0u8, 0u8])?; // 0 TSA, 320 VSA, 0 BSA
-        self.write_command(Instruction::MADCTL)?; // left -> right, bottom -> top RGB
-        self.write_data(&[0b0000_0000])?;
-        self.write_command(Instruction::COLMOD)?; // 16bit 65k colors
-        self.write_data(&[0b0101_0101])?;
-        self.write_command(Instruction::INVON)?; // hack?
-        delay_source.delay_us(10_000);
+        self.set_scroll_area(scroll_area)?;
+        let color_order = config.color_order.unwrap_or(self.panel.color_order);
+        self.madctl = self.madctl.with_color_order(color_order);
+        self.set_orientation(config.orientation)?;
+        self.set_pixel_format(config.pixel_format)?;
+        if let Some(preset) = config.gamma_preset {
+            self.set_gamma_preset(preset)?;
+        }
+        if let Some(curve) = config.gamma_curve.as_ref() {
+            self.set_gamma_curve(curve)?;
+        }
+        if let Some(power) = config.power.as_ref() {
+            self.set_power_config(power)?;
+        }
+        if let Some(frame_rate) = config.frame_rate {
+            self.set_frame_rate(frame_rate)?;
+        }
+        self.run_init_table(config.vendor_commands, delay_source)?;
+        if config.inverted.unwrap_or(self.panel.inverted) {
+            self.write_command(Instruction::INVON)?;
+            delay_source.delay_us(10_000);
+        }
         self.write_command(Instruction::NORON)?; // turn on display
+        self.partial_mode = false;
         delay_source.delay_us(10_000);
         self.write_command(Instruction::DISPON)?; // turn on display
+        self.display_on = true;
         delay_source.delay_us(10_000);
         Ok(())
     }
 
     ///
-    /// Performs a hard reset using the RST pin sequence
+    /// Executes an init command table, sending each command with its data
+    /// and waiting for its delay. The driver follows the memory access control,
+    /// pixel format and power modes set by SWRESET, MADCTL, COLMOD, SLPIN/SLPOUT,
+    /// DISPOFF/DISPON, IDMOFF/IDMON and PTLON/NORON entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `table` - init commands to execute in order
+    /// * `delay_source` - mutable reference to a delay provider
+    ///
+    pub fn run_init_table(
+        &mut self,
+        table: &[InitCommand],
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Result<(), Error<RstE, BlE>> {
+        for entry in table {
+            self.write_raw_command(entry.command)?;
+            if !entry.data.is_empty() {
+                self.write_data(entry.data)?;
+            }
+            self.track_command(entry.command, entry.data);
+            if entry.delay_us > 0 {
+                delay_source.delay_us(entry.delay_us);
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Performs a hard reset using the RST pin sequence.
+    /// Without a reset pin this does nothing, use `init` which also performs a software reset.
     ///
     /// # Arguments
     ///
     /// * `delay_source` - mutable reference to a delay provider
     ///
-    pub fn hard_reset(&mut self, delay_source: &mut impl DelayUs<u32>) -> Result<(), Error<PinE>> {
-        if let Some(rst) = self.rst.as_mut() {
-            rst.set_high().map_err(Error::Pin)?;
-            delay_source.delay_us(10); // ensure the pin change will get registered
-            rst.set_low().map_err(Error::Pin)?;
-            delay_source.delay_us(10); // ensure the pin change will get registered
-            rst.set_high().map_err(Error::Pin)?;
-            delay_source.delay_us(10); // ensure the pin change will get registered
+    pub fn hard_reset(
+        &mut self,
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Result<(), Error<RstE, BlE>> {
+        if !self.rst.is_connected() {
+            return Ok(());
         }
 
+        self.rst.release_reset().map_err(Error::Pin)?;
+        delay_source.delay_us(10); // ensure the pin change will get registered
+        self.rst.assert_reset().map_err(Error::Pin)?;
+        delay_source.delay_us(10); // ensure the pin change will get registered
+        self.rst.release_reset().map_err(Error::Pin)?;
+        delay_source.delay_us(10); // ensure the pin change will get registered
+        self.reset_state();
+
         Ok(())
     }
 
@@ -182,31 +509,133 @@ where
         &mut self,
         state: BacklightState,
         delay_source: &mut impl DelayUs<u32>,
-    ) -> Result<(), Error<PinE>> {
-        if let Some(bl) = self.bl.as_mut() {
-            match state {
-                BacklightState::On => bl.set_high().map_err(Error::Pin)?,
-                BacklightState::Off => bl.set_low().map_err(Error::Pin)?,
-            }
-            delay_source.delay_us(10); // ensure the pin change will get registered
+    ) -> Result<(), Error<RstE, BlE>> {
+        match state {
+            BacklightState::On => self.set_brightness(100)?,
+            BacklightState::Off => self.set_brightness(0)?,
+        }
+        delay_source.delay_us(10); // ensure the pin change will get registered
+        Ok(())
+    }
+
+    ///
+    /// Returns the last set backlight brightness in percent
+    ///
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    ///
+    /// Sets the backlight brightness, a GPIO backlight turns on for any brightness above 0.
+    /// Returns `Error::InvalidArgument` if `percent` is above 100.
+    ///
+    /// # Arguments
+    ///
+    /// * `percent` - brightness from 0 (off) to 100 (full brightness)
+    ///
+    pub fn set_brightness(&mut self, percent: u8) -> Result<(), Error<RstE, BlE>> {
+        if percent > 100 {
+            return Err(Error::InvalidArgument);
+        }
+
+        self.bl.set_brightness(percent).map_err(Error::Backlight)?;
+        self.brightness = percent;
+        Ok(())
+    }
+
+    ///
+    /// Fades the backlight from the current to the given brightness in 1% steps,
+    /// e.g. fade to 0 before `sleep` and back up after `wake`.
+    /// Returns `Error::InvalidArgument` if `percent` is above 100.
+    ///
+    /// # Arguments
+    ///
+    /// * `percent` - target brightness from 0 (off) to 100 (full brightness)
+    /// * `duration_us` - duration of the whole fade in microseconds
+    /// * `delay_source` - mutable reference to a delay provider
+    ///
+    pub fn fade_brightness(
+        &mut self,
+        percent: u8,
+        duration_us: u32,
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Result<(), Error<RstE, BlE>> {
+        if percent > 100 {
+            return Err(Error::InvalidArgument);
+        }
+
+        let steps = u32::from(self.brightness.max(percent) - self.brightness.min(percent));
+        let step_us = duration_us.checked_div(steps).unwrap_or(0);
+        while self.brightness != percent {
+            let next = if self.brightness < percent {
+                self.brightness + 1
+            } else {
+                self.brightness - 1
+            };
+            self.set_brightness(next)?;
+            delay_source.delay_us(step_us);
         }
+
         Ok(())
     }
 
     ///
-    /// Returns currently set orientation
+    /// Returns the panel description this driver was created with
+    ///
+    pub fn panel(&self) -> &Panel {
+        &self.panel
+    }
+
+    ///
+    /// Returns currently set orientation, derived from the memory access control.
+    /// Returns `None` if the mirroring bits don't match any orientation.
+    ///
+    pub fn orientation(&self) -> Option<Orientation> {
+        self.madctl.orientation()
+    }
+
+    ///
+    /// Sets display orientation, keeping the color order and refresh direction
     ///
-    pub fn orientation(&self) -> Orientation {
-        self.orientation
+    pub fn set_orientation(&mut self, orientation: Orientation) -> Result<(), Error<RstE, BlE>> {
+        self.set_memory_access_control(self.madctl.with_orientation(orientation))
     }
 
     ///
-    /// Sets display orientation
+    /// Returns the current memory access control
     ///
-    pub fn set_orientation(&mut self, orientation: Orientation) -> Result<(), Error<PinE>> {
+    pub fn memory_access_control(&self) -> MemoryAccessControl {
+        self.madctl
+    }
+
+    ///
+    /// Sets all memory access control bits. Drawing size, clipping and panel
+    /// offsets follow the new mirroring and row/column exchange, as does `orientation`.
+    ///
+    pub fn set_memory_access_control(
+        &mut self,
+        madctl: MemoryAccessControl,
+    ) -> Result<(), Error<RstE, BlE>> {
         self.write_command(Instruction::MADCTL)?;
-        self.write_data(&[orientation as u8])?;
-        self.orientation = orientation;
+        self.write_data(&[madctl.to_byte()])?;
+        self.madctl = madctl;
+        Ok(())
+    }
+
+    ///
+    /// Returns the current interface pixel format
+    ///
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.pixel_format
+    }
+
+    ///
+    /// Sets the interface pixel format used to send colors to the display
+    ///
+    pub fn set_pixel_format(&mut self, pixel_format: PixelFormat) -> Result<(), Error<RstE, BlE>> {
+        self.write_command(Instruction::COLMOD)?;
+        self.write_data(&[pixel_format as u8])?;
+        self.pixel_format = pixel_format;
         Ok(())
     }
 
@@ -217,16 +646,12 @@ where
     ///
     /// * `x` - x coordinate
     /// * `y` - y coordinate
-    /// * `color` - the Rgb565 color value
+    /// * `color` - the color value
     ///
-    pub fn set_pixel(&mut self, x: u16, y: u16, color: u16) -> Result<(), Error<PinE>> {
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: C) -> Result<(), Error<RstE, BlE>> {
         self.set_address_window(x, y, x, y)?;
         self.write_command(Instruction::RAMWR)?;
-        self.di
-            .send_data(U16BEIter(&mut once(color)))
-            .map_err(|_| Error::DisplayError)?;
-
-        Ok(())
+        self.write_pixels(once(color))
     }
 
     ///
@@ -238,7 +663,7 @@ where
     /// * `sy` - y coordinate start
     /// * `ex` - x coordinate end
     /// * `ey` - y coordinate end
-    /// * `colors` - anything that can provide `IntoIterator<Item = u16>` to iterate over pixel data
+    /// * `colors` - anything that can provide `IntoIterator<Item = C>` to iterate over pixel data
     ///
     pub fn set_pixels<T>(
         &mut self,
@@ -247,57 +672,204 @@ where
         ex: u16,
         ey: u16,
         colors: T,
-    ) -> Result<(), Error<PinE>>
+    ) -> Result<(), Error<RstE, BlE>>
     where
-        T: IntoIterator<Item = u16>,
+        T: IntoIterator<Item = C>,
     {
         self.set_address_window(sx, sy, ex, ey)?;
         self.write_command(Instruction::RAMWR)?;
-        self.di
-            .send_data(U16BEIter(&mut colors.into_iter()))
-            .map_err(|_| Error::DisplayError)
+        self.write_pixels(colors)
+    }
+
+    ///
+    /// Continues writing pixel colors (RAMWRC) after the last pixel written by
+    /// `set_pixel`, `set_pixels` or a previous call, within the same rectangle bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `colors` - anything that can provide `IntoIterator<Item = C>` to iterate over pixel data
+    ///
+    pub fn set_pixels_continue<T>(&mut self, colors: T) -> Result<(), Error<RstE, BlE>>
+    where
+        T: IntoIterator<Item = C>,
+    {
+        self.write_command(Instruction::RAMWRC)?;
+        self.write_pixels(colors)
+    }
+
+    ///
+    /// Returns the current vertical scroll area
+    ///
+    pub fn scroll_area(&self) -> ScrollArea {
+        self.scroll_area
+    }
+
+    ///
+    /// Defines the top fixed, scrolling and bottom fixed areas and resets the scroll offset
+    ///
+    /// # Arguments
+    ///
+    /// * `area` - scroll area, the three parts must add up to the RAM height of the panel
+    ///
+    pub fn set_scroll_area(&mut self, area: ScrollArea) -> Result<(), Error<RstE, BlE>> {
+        if !area.is_valid(&self.panel) {
+            return Err(Error::InvalidArgument);
+        }
+
+        self.write_command(Instruction::VSCRDER)?;
+        self.write_data(&area.top_fixed.to_be_bytes())?;
+        self.write_data(&area.scroll.to_be_bytes())?;
+        self.write_data(&area.bottom_fixed.to_be_bytes())?;
+        self.scroll_area = area;
+        self.set_scroll_offset(0)
     }
 
     ///
-    /// Sets scroll offset "shifting" the displayed picture
+    /// Sets scroll offset "shifting" the displayed picture along the RAM rows.
+    /// See `set_screen_scroll_offset` for scrolling relative to the current orientation.
+    ///
     /// # Arguments
     ///
-    /// * `offset` - scroll offset in pixels
+    /// * `offset` - scroll offset in pixels, relative to the start of the scroll area
     ///
-    pub fn set_scroll_offset(&mut self, offset: u16) -> Result<(), Error<PinE>> {
+    pub fn set_scroll_offset(&mut self, offset: u16) -> Result<(), Error<RstE, BlE>> {
+        let offset = offset % self.scroll_area.scroll;
+        let line = self.scroll_area.top_fixed + offset;
         self.write_command(Instruction::VSCAD)?;
-        self.write_data(&offset.to_be_bytes())
+        self.write_data(&line.to_be_bytes())?;
+        self.scroll_offset = offset;
+        Ok(())
     }
 
     ///
     /// Release resources allocated to this driver back.
-    /// This returns the display interface and the RST pin deconstructing the driver.
+    /// This returns the display interface, the RST pin and the backlight deconstructing the driver.
     ///
-    pub fn release(self) -> (DI, Option<OUT>, Option<OUT>) {
+    pub fn release(self) -> (DI, RST, BL) {
         (self.di, self.rst, self.bl)
     }
 
-    fn write_command(&mut self, command: Instruction) -> Result<(), Error<PinE>> {
+    // Tracks the display modes of the controller after a reset.
+    fn reset_state(&mut self) {
+        self.sleeping = true;
+        self.display_on = false;
+        self.idle_mode = false;
+        self.partial_mode = false;
+    }
+
+    // Updates the cached controller state after a raw command.
+    fn track_command(&mut self, command: u8, data: &[u8]) {
+        const SWRESET: u8 = Instruction::SWRESET as u8;
+        const SLPIN: u8 = Instruction::SLPIN as u8;
+        const SLPOUT: u8 = Instruction::SLPOUT as u8;
+        const PTLON: u8 = Instruction::PTLON as u8;
+        const NORON: u8 = Instruction::NORON as u8;
+        const DISPOFF: u8 = Instruction::DISPOFF as u8;
+        const DISPON: u8 = Instruction::DISPON as u8;
+        const MADCTL: u8 = Instruction::MADCTL as u8;
+        const IDMOFF: u8 = Instruction::IDMOFF as u8;
+        const IDMON: u8 = Instruction::IDMON as u8;
+        const COLMOD: u8 = Instruction::COLMOD as u8;
+
+        match (command, data.first()) {
+            (SWRESET, _) => {
+                self.reset_state();
+                self.madctl = MemoryAccessControl::from_byte(0);
+                self.pixel_format = PixelFormat::Rgb666;
+            }
+            (SLPIN, _) => self.sleeping = true,
+            (SLPOUT, _) => self.sleeping = false,
+            (PTLON, _) => self.partial_mode = true,
+            (NORON, _) => self.partial_mode = false,
+            (DISPOFF, _) => self.display_on = false,
+            (DISPON, _) => self.display_on = true,
+            (IDMOFF, _) => self.idle_mode = false,
+            (IDMON, _) => self.idle_mode = true,
+            (MADCTL, Some(&value)) => self.madctl = MemoryAccessControl::from_byte(value),
+            (COLMOD, Some(&value)) => {
+                if let Some(pixel_format) = PixelFormat::from_byte(value) {
+                    self.pixel_format = pixel_format;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn write_command(&mut self, command: Instruction) -> Result<(), Error<RstE, BlE>> {
+        self.write_raw_command(command as u8)
+    }
+
+    fn write_raw_command(&mut self, command: u8) -> Result<(), Error<RstE, BlE>> {
         self.di
-            .send_commands(U8Iter(&mut once(command as u8)))
+            .send_commands(U8Iter(&mut once(command)))
             .map_err(|_| Error::DisplayError)?;
         Ok(())
     }
 
-    fn write_data(&mut self, data: &[u8]) -> Result<(), Error<PinE>> {
+    fn write_data(&mut self, data: &[u8]) -> Result<(), Error<RstE, BlE>> {
         self.di
             .send_data(U8Iter(&mut data.iter().cloned()))
             .map_err(|_| Error::DisplayError)
     }
 
-    // Sets the address window for the display.
+    // Sets pixel colors in given rectangle bounds of the controller RAM, ignoring the panel offset.
+    pub(crate) fn set_ram_pixels<T>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: T,
+    ) -> Result<(), Error<RstE, BlE>>
+    where
+        T: IntoIterator<Item = C>,
+    {
+        self.set_ram_window(sx, sy, ex, ey)?;
+        self.write_command(Instruction::RAMWR)?;
+        self.write_pixels(colors)
+    }
+
+    // Sends pixel data packed according to the current pixel format.
+    fn write_pixels<T>(&mut self, colors: T) -> Result<(), Error<RstE, BlE>>
+    where
+        T: IntoIterator<Item = C>,
+    {
+        let colors = colors.into_iter();
+        let result = match self.pixel_format {
+            PixelFormat::Rgb444 => self
+                .di
+                .send_data(U8Iter(&mut Rgb444Bytes::new(colors.map(color::to_rgb444)))),
+            PixelFormat::Rgb565 => self
+                .di
+                .send_data(U16BEIter(&mut colors.map(color::to_rgb565))),
+            PixelFormat::Rgb666 => self
+                .di
+                .send_data(U8Iter(&mut colors.flat_map(color::to_rgb666))),
+        };
+
+        result.map_err(|_| Error::DisplayError)
+    }
+
+    // Sets the address window for the display, translated by the panel offset.
     fn set_address_window(
         &mut self,
         sx: u16,
         sy: u16,
         ex: u16,
         ey: u16,
-    ) -> Result<(), Error<PinE>> {
+    ) -> Result<(), Error<RstE, BlE>> {
+        let (ox, oy) = self.panel.offset(self.madctl);
+        self.set_ram_window(sx + ox, sy + oy, ex + ox, ey + oy)
+    }
+
+    // Sets the address window in controller RAM coordinates.
+    fn set_ram_window(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+    ) -> Result<(), Error<RstE, BlE>> {
         self.write_command(Instruction::CASET)?;
         self.write_data(&sx.to_be_bytes())?;
         self.write_data(&ex.to_be_bytes())?;
@@ -306,10 +878,125 @@ where
         self.write_data(&ey.to_be_bytes())
     }
 
+    ///
+    /// Sends an instruction followed by its raw parameter bytes.
+    /// The driver state follows the same instructions as in `run_init_table`.
+    ///
+    /// # Arguments
+    ///
+    /// * `instruction` - the instruction to send
+    /// * `params` - parameter bytes, may be empty
+    ///
+    pub fn write_instruction(
+        &mut self,
+        instruction: Instruction,
+        params: &[u8],
+    ) -> Result<(), Error<RstE, BlE>> {
+        self.write_command(instruction)?;
+        if !params.is_empty() {
+            self.write_data(params)?;
+        }
+        self.track_command(instruction as u8, params);
+        Ok(())
+    }
+
+    ///
+    /// Turns display inversion on or off
+    ///
+    pub fn set_inversion(&mut self, inverted: bool) -> Result<(), Error<RstE, BlE>> {
+        if inverted {
+            self.write_command(Instruction::INVON)
+        } else {
+            self.write_command(Instruction::INVOFF)
+        }
+    }
+
+    ///
+    /// Sets the scanline at which the tearing effect output signal is generated
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - scanline number, only the lower 9 bits are used
+    ///
+    pub fn set_tearing_scanline(&mut self, line: u16) -> Result<(), Error<RstE, BlE>> {
+        self.write_command(Instruction::TESCAN)?;
+        self.write_data(&(line & 0x01FF).to_be_bytes())
+    }
+
+    ///
+    /// Selects one of the predefined gamma curves
+    ///
+    pub fn set_gamma_preset(&mut self, preset: GammaPreset) -> Result<(), Error<RstE, BlE>> {
+        self.write_command(Instruction::GAMSET)?;
+        self.write_data(&[preset as u8])
+    }
+
+    ///
+    /// Writes custom positive and negative voltage gamma tables
+    ///
+    pub fn set_gamma_curve(&mut self, curve: &GammaCurve) -> Result<(), Error<RstE, BlE>> {
+        self.write_command(Instruction::PVGAMCTRL)?;
+        self.write_data(&curve.positive.to_bytes())?;
+        self.write_command(Instruction::NVGAMCTRL)?;
+        self.write_data(&curve.negative.to_bytes())
+    }
+
+    ///
+    /// Sets the frame rate in normal mode
+    ///
+    pub fn set_frame_rate(&mut self, frame_rate: FrameRate) -> Result<(), Error<RstE, BlE>> {
+        if !frame_rate.is_valid() {
+            return Err(Error::InvalidArgument);
+        }
+
+        self.write_command(Instruction::FRCTRL2)?;
+        self.write_data(&[frame_rate.to_byte()])
+    }
+
+    ///
+    /// Sets the porch settings
+    ///
+    pub fn set_porch(&mut self, porch: &PorchConfig) -> Result<(), Error<RstE, BlE>> {
+        if !porch.is_valid() {
+            return Err(Error::InvalidArgument);
+        }
+
+        self.write_command(Instruction::PORCTRL)?;
+        self.write_data(&porch.to_bytes())
+    }
+
+    ///
+    /// Writes the porch, gate, VCOM, LCM and power control registers
+    ///
+    pub fn set_power_config(&mut self, config: &PanelPowerConfig) -> Result<(), Error<RstE, BlE>> {
+        if !config.is_valid() {
+            return Err(Error::InvalidArgument);
+        }
+
+        self.set_porch(&config.porch)?;
+        self.write_command(Instruction::GCTRL)?;
+        self.write_data(&[config.gate_control()])?;
+        self.write_command(Instruction::VCOMS)?;
+        self.write_data(&[config.vcom])?;
+        self.write_command(Instruction::LCMCTRL)?;
+        self.write_data(&[config.lcm_control])?;
+        self.write_command(Instruction::VDVVRHEN)?; // VDV and VRH from the registers below
+        self.write_data(&[0x01, 0xFF])?;
+        self.write_command(Instruction::VRHS)?;
+        self.write_data(&[config.vrh])?;
+        self.write_command(Instruction::VDVS)?;
+        self.write_data(&[config.vdv])?;
+        self.write_command(Instruction::PWCTRL1)?;
+        self.write_data(&config.power_control())
+    }
+
     ///
     /// Configures the tearing effect output.
     ///
-    pub fn set_tearing_effect(&mut self, tearing_effect: TearingEffect) -> Result<(), Error<PinE>> {
+    pub fn set_tearing_effect(
+        &mut self,
+        tearing_effect: TearingEffect,
+    ) -> Result<(), Error<RstE, BlE>> {
         match tearing_effect {
             TearingEffect::Off => self.write_command(Instruction::TEOFF),
             TearingEffect::Vertical => {
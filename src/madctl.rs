@@ -0,0 +1,149 @@
+//! Memory data access control (MADCTL).
+
+use crate::{ColorOrder, Orientation};
+
+///
+/// Memory data access control bits.
+///
+/// `Orientation` sets the MY, MX and MV bits for the four rotations,
+/// this type additionally allows mirroring and changing the refresh direction.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct MemoryAccessControl {
+    /// Invert the row (page) address order (MY)
+    pub mirror_y: bool,
+    /// Invert the column address order (MX)
+    pub mirror_x: bool,
+    /// Exchange rows and columns (MV)
+    pub swap_xy: bool,
+    /// Refresh the panel from bottom to top (ML)
+    pub refresh_bottom_to_top: bool,
+    /// Sub-pixel color order (RGB)
+    pub color_order: ColorOrder,
+    /// Refresh the panel from right to left (MH)
+    pub refresh_right_to_left: bool,
+}
+
+impl MemoryAccessControl {
+    ///
+    /// Returns the access control with the given color order.
+    ///
+    pub const fn with_color_order(self, color_order: ColorOrder) -> Self {
+        Self {
+            color_order,
+            ..self
+        }
+    }
+
+    ///
+    /// Returns the access control with the column address order inverted.
+    ///
+    pub const fn mirrored_x(self) -> Self {
+        Self {
+            mirror_x: !self.mirror_x,
+            ..self
+        }
+    }
+
+    ///
+    /// Returns the access control with the row address order inverted.
+    ///
+    pub const fn mirrored_y(self) -> Self {
+        Self {
+            mirror_y: !self.mirror_y,
+            ..self
+        }
+    }
+
+    ///
+    /// Returns the MADCTL register value.
+    ///
+    pub fn to_byte(self) -> u8 {
+        (self.mirror_y as u8) << 7
+            | (self.mirror_x as u8) << 6
+            | (self.swap_xy as u8) << 5
+            | (self.refresh_bottom_to_top as u8) << 4
+            | self.color_order as u8
+            | (self.refresh_right_to_left as u8) << 2
+    }
+
+    ///
+    /// Decodes a MADCTL register value.
+    ///
+    pub fn from_byte(value: u8) -> Self {
+        Self {
+            mirror_y: value & 0b1000_0000 != 0,
+            mirror_x: value & 0b0100_0000 != 0,
+            swap_xy: value & 0b0010_0000 != 0,
+            refresh_bottom_to_top: value & 0b0001_0000 != 0,
+            color_order: if value & 0b0000_1000 != 0 {
+                ColorOrder::Bgr
+            } else {
+                ColorOrder::Rgb
+            },
+            refresh_right_to_left: value & 0b0000_0100 != 0,
+        }
+    }
+
+    ///
+    /// Returns the orientation matching the MY, MX and MV bits,
+    /// `None` for mirrored combinations that are no rotation.
+    ///
+    pub fn orientation(&self) -> Option<Orientation> {
+        let orientations = [
+            Orientation::Portrait,
+            Orientation::Landscape,
+            Orientation::PortraitSwapped,
+            Orientation::LandscapeSwapped,
+        ];
+
+        orientations
+            .iter()
+            .copied()
+            .find(|&orientation| self.with_orientation(orientation) == *self)
+    }
+
+    /// Replaces the MY, MX and MV bits with the ones of the orientation.
+    pub(crate) fn with_orientation(self, orientation: Orientation) -> Self {
+        let rotation = Self::from(orientation);
+
+        Self {
+            mirror_y: rotation.mirror_y,
+            mirror_x: rotation.mirror_x,
+            swap_xy: rotation.swap_xy,
+            ..self
+        }
+    }
+}
+
+impl From<Orientation> for MemoryAccessControl {
+    fn from(orientation: Orientation) -> Self {
+        Self::from_byte(orientation as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orientation_follows_rotation_bits() {
+        let orientations = [
+            Orientation::Portrait,
+            Orientation::Landscape,
+            Orientation::PortraitSwapped,
+            Orientation::LandscapeSwapped,
+        ];
+
+        for &orientation in orientations.iter() {
+            let madctl = MemoryAccessControl::from(orientation).with_color_order(ColorOrder::Bgr);
+            assert_eq!(madctl.orientation(), Some(orientation));
+        }
+
+        let mirrored = MemoryAccessControl {
+            mirror_x: true,
+            ..MemoryAccessControl::default()
+        };
+        assert_eq!(mirrored.orientation(), None);
+    }
+}
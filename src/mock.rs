@@ -0,0 +1,125 @@
+//! Test doubles for the display interface, pins and delays.
+
+extern crate std;
+
+use std::vec::Vec;
+
+use core::convert::Infallible;
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::pixelcolor::Rgb565;
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::digital::v2::OutputPin;
+
+use crate::{NoPin, Panel, ReadDataCommand, ST7789};
+
+/// A transfer seen by the interface
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Transfer {
+    Command(u8),
+    /// Data bytes, consecutive data transfers are merged
+    Data(Vec<u8>),
+    /// A read command and the number of bytes read
+    Read(u8, usize),
+}
+
+/// Display interface recording every transfer and answering reads from `responses`
+#[derive(Default)]
+pub struct MockInterface {
+    pub transfers: Vec<Transfer>,
+    pub responses: Vec<u8>,
+}
+
+impl MockInterface {
+    /// Data bytes sent with the last occurrence of the given command
+    pub fn data_of(&self, command: u8) -> Option<&[u8]> {
+        let index = self
+            .transfers
+            .iter()
+            .rposition(|transfer| *transfer == Transfer::Command(command))?;
+
+        match self.transfers.get(index + 1) {
+            Some(Transfer::Data(data)) => Some(data),
+            _ => Some(&[]),
+        }
+    }
+
+    fn push_data(&mut self, bytes: impl Iterator<Item = u8>) {
+        if let Some(Transfer::Data(data)) = self.transfers.last_mut() {
+            data.extend(bytes);
+        } else {
+            self.transfers.push(Transfer::Data(bytes.collect()));
+        }
+    }
+}
+
+impl WriteOnlyDataCommand for MockInterface {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        match cmd {
+            DataFormat::U8Iter(iter) => {
+                for command in iter {
+                    self.transfers.push(Transfer::Command(command));
+                }
+                Ok(())
+            }
+            _ => Err(DisplayError::DataFormatNotImplemented),
+        }
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        match buf {
+            DataFormat::U8(bytes) => self.push_data(bytes.iter().copied()),
+            DataFormat::U8Iter(iter) => self.push_data(iter),
+            DataFormat::U16BEIter(iter) => self.push_data(iter.flat_map(|v| v.to_be_bytes())),
+            _ => return Err(DisplayError::DataFormatNotImplemented),
+        }
+
+        Ok(())
+    }
+}
+
+impl ReadDataCommand for MockInterface {
+    fn read_data(&mut self, command: u8, buf: &mut [u8]) -> Result<(), DisplayError> {
+        if self.responses.len() < buf.len() {
+            return Err(DisplayError::BusWriteError);
+        }
+
+        let len = buf.len();
+        buf.copy_from_slice(&self.responses[..len]);
+        self.responses.drain(..len);
+        self.transfers.push(Transfer::Read(command, len));
+        Ok(())
+    }
+}
+
+/// Output pin recording its levels, `true` for high
+#[derive(Default)]
+pub struct MockPin {
+    pub levels: Vec<bool>,
+}
+
+impl OutputPin for MockPin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.levels.push(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.levels.push(true);
+        Ok(())
+    }
+}
+
+/// Delay returning immediately
+pub struct NoDelay;
+
+impl DelayUs<u32> for NoDelay {
+    fn delay_us(&mut self, _us: u32) {}
+}
+
+/// A driver without pins talking to a mock interface
+pub fn display(panel: Panel) -> ST7789<MockInterface, NoPin, NoPin, Rgb565> {
+    ST7789::new(MockInterface::default(), NoPin, NoPin, panel)
+}
@@ -0,0 +1,251 @@
+//! Display modes.
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::RgbColor;
+use embedded_hal::blocking::delay::DelayUs;
+
+use crate::instruction::Instruction;
+use crate::{Backlight, Error, ResetPin, ST7789};
+
+/// Time to wait after SLPIN or SLPOUT before the opposite command may be sent
+pub(crate) const SLEEP_TRANSITION_US: u32 = 120_000;
+
+///
+/// Power state of the display.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PowerState {
+    /// Sleep mode, the state after reset
+    Sleep,
+    /// Awake with the display output turned off
+    DisplayOff,
+    /// Awake and showing the RAM contents
+    DisplayOn,
+}
+
+impl<DI, RST, BL, C, RstE, BlE> ST7789<DI, RST, BL, C>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+{
+    ///
+    /// Defines the rows shown in partial mode, the remaining rows are turned off.
+    /// If `start` is greater than `end` the area wraps at the end of the controller RAM,
+    /// not the panel: with a row offset it also spans the RAM rows outside the glass.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - first visible panel row in portrait orientation
+    /// * `end` - last visible panel row in portrait orientation
+    ///
+    pub fn set_partial_area(&mut self, start: u16, end: u16) -> Result<(), Error<RstE, BlE>> {
+        if start >= self.panel.height || end >= self.panel.height {
+            return Err(Error::InvalidArgument);
+        }
+
+        let start = start + self.panel.offset_y;
+        let end = end + self.panel.offset_y;
+        self.write_command(Instruction::PTLAR)?;
+        self.write_data(&start.to_be_bytes())?;
+        self.write_data(&end.to_be_bytes())
+    }
+
+    ///
+    /// Returns true if the display is in partial mode
+    ///
+    pub fn is_partial_mode(&self) -> bool {
+        self.partial_mode
+    }
+
+    ///
+    /// Enters partial mode showing only the area set by `set_partial_area`
+    ///
+    pub fn enter_partial_mode(&mut self) -> Result<(), Error<RstE, BlE>> {
+        self.write_command(Instruction::PTLON)?;
+        self.partial_mode = true;
+        Ok(())
+    }
+
+    ///
+    /// Leaves partial mode and returns to normal display mode
+    ///
+    pub fn exit_partial_mode(&mut self) -> Result<(), Error<RstE, BlE>> {
+        self.write_command(Instruction::NORON)?;
+        self.partial_mode = false;
+        Ok(())
+    }
+
+    ///
+    /// Returns the current power state
+    ///
+    pub fn power_state(&self) -> PowerState {
+        if self.sleeping {
+            PowerState::Sleep
+        } else if self.display_on {
+            PowerState::DisplayOn
+        } else {
+            PowerState::DisplayOff
+        }
+    }
+
+    ///
+    /// Enters sleep mode (SLPIN) and waits the 120ms required before waking up again.
+    /// Returns `Error::InvalidPowerState` if the display is already sleeping.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay_source` - mutable reference to a delay provider
+    ///
+    pub fn sleep(&mut self, delay_source: &mut impl DelayUs<u32>) -> Result<(), Error<RstE, BlE>> {
+        if self.sleeping {
+            return Err(Error::InvalidPowerState(self.power_state()));
+        }
+
+        self.write_command(Instruction::SLPIN)?;
+        self.sleeping = true;
+        delay_source.delay_us(SLEEP_TRANSITION_US);
+        Ok(())
+    }
+
+    ///
+    /// Leaves sleep mode (SLPOUT) and waits the 120ms required before sleeping again.
+    /// The display output returns to its state before sleeping.
+    /// Returns `Error::InvalidPowerState` if the display is not sleeping.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay_source` - mutable reference to a delay provider
+    ///
+    pub fn wake(&mut self, delay_source: &mut impl DelayUs<u32>) -> Result<(), Error<RstE, BlE>> {
+        if !self.sleeping {
+            return Err(Error::InvalidPowerState(self.power_state()));
+        }
+
+        self.write_command(Instruction::SLPOUT)?;
+        self.sleeping = false;
+        delay_source.delay_us(SLEEP_TRANSITION_US);
+        Ok(())
+    }
+
+    ///
+    /// Turns the display output on (DISPON) or off (DISPOFF) without changing RAM contents.
+    /// Returns `Error::InvalidPowerState` if the display is sleeping.
+    ///
+    pub fn set_display_on(&mut self, on: bool) -> Result<(), Error<RstE, BlE>> {
+        if self.sleeping {
+            return Err(Error::InvalidPowerState(self.power_state()));
+        }
+
+        if on {
+            self.write_command(Instruction::DISPON)?;
+        } else {
+            self.write_command(Instruction::DISPOFF)?;
+        }
+        self.display_on = on;
+        Ok(())
+    }
+
+    ///
+    /// Returns true if idle mode with reduced colors is active
+    ///
+    pub fn is_idle_mode(&self) -> bool {
+        self.idle_mode
+    }
+
+    ///
+    /// Enters (IDMON) or leaves (IDMOFF) idle mode, which shows 8 colors
+    /// using only the MSB of each color component to save power
+    ///
+    pub fn set_idle_mode(&mut self, idle: bool) -> Result<(), Error<RstE, BlE>> {
+        if idle {
+            self.write_command(Instruction::IDMON)?;
+        } else {
+            self.write_command(Instruction::IDMOFF)?;
+        }
+        self.idle_mode = idle;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{display, MockInterface, MockPin, NoDelay};
+    use crate::{InitCommand, MemoryAccessControl, NoPin, Orientation, Panel, PixelFormat};
+    use embedded_graphics_core::pixelcolor::Rgb565;
+
+    #[test]
+    fn sleep_twice_fails() {
+        let mut display = display(Panel::default());
+        display.wake(&mut NoDelay).unwrap();
+        display.sleep(&mut NoDelay).unwrap();
+
+        assert!(matches!(
+            display.sleep(&mut NoDelay),
+            Err(Error::InvalidPowerState(PowerState::Sleep))
+        ));
+    }
+
+    #[test]
+    fn wake_while_awake_fails() {
+        let mut display = display(Panel::default());
+        display.wake(&mut NoDelay).unwrap();
+
+        assert!(matches!(
+            display.wake(&mut NoDelay),
+            Err(Error::InvalidPowerState(PowerState::DisplayOff))
+        ));
+    }
+
+    #[test]
+    fn display_on_while_asleep_fails() {
+        let mut display = display(Panel::default());
+
+        assert!(matches!(
+            display.set_display_on(true),
+            Err(Error::InvalidPowerState(PowerState::Sleep))
+        ));
+        assert!(display.di.transfers.is_empty());
+    }
+
+    #[test]
+    fn hard_reset_returns_to_sleep() {
+        let mut display: ST7789<_, _, _, Rgb565> = ST7789::new(
+            MockInterface::default(),
+            MockPin::default(),
+            NoPin,
+            Panel::default(),
+        );
+        display.wake(&mut NoDelay).unwrap();
+        display.set_display_on(true).unwrap();
+        assert_eq!(display.power_state(), PowerState::DisplayOn);
+
+        display.hard_reset(&mut NoDelay).unwrap();
+
+        assert_eq!(display.power_state(), PowerState::Sleep);
+        assert_eq!(display.rst.levels, [true, false, true]);
+    }
+
+    #[test]
+    fn init_table_updates_state() {
+        const TABLE: &[InitCommand] = &[
+            InitCommand::new(0x11, &[], 0),     // SLPOUT
+            InitCommand::new(0x36, &[0x60], 0), // MADCTL landscape
+            InitCommand::new(0x3A, &[0x66], 0), // COLMOD 18bit
+            InitCommand::new(0x12, &[], 0),     // PTLON
+            InitCommand::new(0x29, &[], 0),     // DISPON
+        ];
+        let mut display = display(Panel::default());
+        display.run_init_table(TABLE, &mut NoDelay).unwrap();
+
+        assert_eq!(display.power_state(), PowerState::DisplayOn);
+        assert_eq!(
+            display.memory_access_control(),
+            MemoryAccessControl::from(Orientation::Landscape)
+        );
+        assert_eq!(display.pixel_format(), PixelFormat::Rgb666);
+        assert!(display.is_partial_mode());
+    }
+}
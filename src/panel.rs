@@ -0,0 +1,213 @@
+//! Panel geometry description.
+
+use crate::{ColorOrder, MemoryAccessControl};
+
+///
+/// Describes the glass attached to the ST7789 controller.
+///
+/// All sizes and offsets are given in the native (portrait) orientation, the driver
+/// swaps and mirrors them as required by the current memory access control.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Panel {
+    /// Visible width in pixels
+    pub width: u16,
+    /// Visible height in pixels
+    pub height: u16,
+    /// Width of the controller RAM in pixels
+    pub ram_width: u16,
+    /// Height of the controller RAM in pixels
+    pub ram_height: u16,
+    /// First RAM column of the visible area
+    pub offset_x: u16,
+    /// First RAM row of the visible area
+    pub offset_y: u16,
+    /// Whether the panel needs display inversion (INVON) to show correct colors
+    pub inverted: bool,
+    /// Sub-pixel color order of the panel
+    pub color_order: ColorOrder,
+}
+
+impl Panel {
+    /// PineTime watch, 240x240
+    pub const PINETIME: Panel = Panel::new(240, 240);
+
+    /// LilyGO TTGO T-Display, 135x240
+    pub const TTGO_T_DISPLAY: Panel = Panel::new(135, 240).with_offset(52, 40);
+
+    /// Waveshare 1.69" module, 240x280
+    pub const WAVESHARE_1_69: Panel = Panel::new(240, 280).with_offset(0, 20);
+
+    /// Waveshare 1.47" module, 172x320
+    pub const WAVESHARE_1_47: Panel = Panel::new(172, 320).with_offset(34, 0);
+
+    /// Waveshare 2" module, 240x320
+    pub const WAVESHARE_2_0: Panel = Panel::new(240, 320);
+
+    ///
+    /// Creates a panel description with the given visible size backed
+    /// by the full 240x320 ST7789 RAM, using inverted colors in RGB order.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - visible width in pixels in portrait orientation
+    /// * `height` - visible height in pixels in portrait orientation
+    ///
+    pub const fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            ram_width: 240,
+            ram_height: 320,
+            offset_x: 0,
+            offset_y: 0,
+            inverted: true,
+            color_order: ColorOrder::Rgb,
+        }
+    }
+
+    ///
+    /// Returns the panel description with the visible area starting at the given
+    /// RAM column and row in portrait orientation. Offsets for the other orientations
+    /// are derived from these and the RAM size.
+    ///
+    pub const fn with_offset(self, offset_x: u16, offset_y: u16) -> Self {
+        Self {
+            offset_x,
+            offset_y,
+            ..self
+        }
+    }
+
+    ///
+    /// Returns the panel description with a different controller RAM size.
+    ///
+    pub const fn with_ram_size(self, ram_width: u16, ram_height: u16) -> Self {
+        Self {
+            ram_width,
+            ram_height,
+            ..self
+        }
+    }
+
+    ///
+    /// Returns the panel description with display inversion turned on or off.
+    ///
+    pub const fn with_inversion(self, inverted: bool) -> Self {
+        Self { inverted, ..self }
+    }
+
+    ///
+    /// Returns the panel description with a different sub-pixel color order.
+    ///
+    pub const fn with_color_order(self, color_order: ColorOrder) -> Self {
+        Self {
+            color_order,
+            ..self
+        }
+    }
+
+    ///
+    /// Returns true if the visible area is not empty and lies within the controller RAM.
+    ///
+    pub fn is_valid(&self) -> bool {
+        let fits_x = u32::from(self.offset_x) + u32::from(self.width) <= u32::from(self.ram_width);
+        let fits_y =
+            u32::from(self.offset_y) + u32::from(self.height) <= u32::from(self.ram_height);
+
+        self.width > 0 && self.height > 0 && fits_x && fits_y
+    }
+
+    /// Visible size (x, y) for the given memory access control.
+    pub(crate) fn size(&self, madctl: MemoryAccessControl) -> (u16, u16) {
+        if madctl.swap_xy {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        }
+    }
+
+    /// Offset (x, y) of the visible area in RAM coordinates for the given memory access control.
+    /// Mirrored offsets of invalid panels saturate at 0.
+    pub(crate) fn offset(&self, madctl: MemoryAccessControl) -> (u16, u16) {
+        let column = if madctl.mirror_x {
+            self.ram_width
+                .saturating_sub(self.width)
+                .saturating_sub(self.offset_x)
+        } else {
+            self.offset_x
+        };
+        let row = if madctl.mirror_y {
+            self.ram_height
+                .saturating_sub(self.height)
+                .saturating_sub(self.offset_y)
+        } else {
+            self.offset_y
+        };
+
+        if madctl.swap_xy {
+            (row, column)
+        } else {
+            (column, row)
+        }
+    }
+
+    /// Controller RAM size (x, y) for the given memory access control.
+    pub(crate) fn ram_size(&self, madctl: MemoryAccessControl) -> (u16, u16) {
+        if madctl.swap_xy {
+            (self.ram_height, self.ram_width)
+        } else {
+            (self.ram_width, self.ram_height)
+        }
+    }
+}
+
+impl Default for Panel {
+    fn default() -> Self {
+        Self::new(240, 320)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Orientation;
+
+    #[test]
+    fn offset_follows_orientation() {
+        let panel = Panel::TTGO_T_DISPLAY;
+        let offsets = [
+            (Orientation::Portrait, (52, 40)),
+            (Orientation::Landscape, (40, 53)),
+            (Orientation::PortraitSwapped, (53, 40)),
+            (Orientation::LandscapeSwapped, (40, 52)),
+        ];
+
+        for &(orientation, expected) in offsets.iter() {
+            assert_eq!(
+                panel.offset(MemoryAccessControl::from(orientation)),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn size_swaps_in_landscape() {
+        let panel = Panel::TTGO_T_DISPLAY;
+
+        assert_eq!(panel.size(Orientation::Portrait.into()), (135, 240));
+        assert_eq!(panel.size(Orientation::Landscape.into()), (240, 135));
+    }
+
+    #[test]
+    fn geometry_must_fit_ram() {
+        assert!(Panel::WAVESHARE_1_69.is_valid());
+        assert!(Panel::TTGO_T_DISPLAY.is_valid());
+        assert!(!Panel::new(240, 320).with_offset(0, 20).is_valid());
+        assert!(!Panel::new(0, 240).is_valid());
+
+        let panel = Panel::new(240, 320).with_offset(0, 20);
+        let madctl = MemoryAccessControl::from(Orientation::PortraitSwapped);
+        assert_eq!(panel.offset(madctl), (0, 0));
+    }
+}
@@ -0,0 +1,284 @@
+//! Power, porch and frame rate control registers.
+
+// Frame rates in Hz for RTNA 0x00..=0x1F with the default porch settings.
+const FRAME_RATES: [u8; 32] = [
+    119, 111, 105, 99, 94, 90, 86, 82, 78, 75, 72, 69, 67, 64, 62, 60, 58, 57, 55, 53, 52, 50, 49,
+    48, 46, 45, 44, 43, 42, 41, 40, 39,
+];
+
+///
+/// Inversion selection in normal mode (FRCTRL2 NLA).
+///
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InversionMode {
+    /// Dot inversion
+    Dot = 0b000,
+    /// Column inversion
+    Column = 0b111,
+}
+
+impl Default for InversionMode {
+    fn default() -> Self {
+        Self::Dot
+    }
+}
+
+///
+/// Frame rate in normal mode (FRCTRL2).
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FrameRate {
+    /// Frame rate divider RTNA, 0x00 (119Hz) to 0x1F (39Hz)
+    pub rtna: u8,
+    /// Inversion selection
+    pub inversion: InversionMode,
+}
+
+impl FrameRate {
+    ///
+    /// Creates a frame rate setting from a raw RTNA value with dot inversion
+    ///
+    pub const fn new(rtna: u8) -> Self {
+        Self {
+            rtna,
+            inversion: InversionMode::Dot,
+        }
+    }
+
+    ///
+    /// Returns the setting closest to the given frame rate in Hz
+    /// assuming the default porch settings
+    ///
+    pub fn from_hz(hz: u8) -> Self {
+        let rtna = FRAME_RATES
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, rate)| (**rate as i16 - hz as i16).abs())
+            .map(|(rtna, _)| rtna as u8)
+            .unwrap_or(0x0F);
+
+        Self::new(rtna)
+    }
+
+    ///
+    /// Returns the frame rate in Hz assuming the default porch settings
+    ///
+    pub fn hz(&self) -> Option<u8> {
+        FRAME_RATES.get(usize::from(self.rtna)).copied()
+    }
+
+    pub(crate) fn is_valid(&self) -> bool {
+        self.rtna <= 0x1F
+    }
+
+    pub(crate) fn to_byte(self) -> u8 {
+        (self.inversion as u8) << 5 | self.rtna
+    }
+}
+
+impl Default for FrameRate {
+    fn default() -> Self {
+        Self::new(0x0F) // 60Hz
+    }
+}
+
+///
+/// Porch settings (PORCTRL).
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PorchConfig {
+    /// Back porch in normal mode, 0x01 to 0x7F
+    pub back: u8,
+    /// Front porch in normal mode, 0x01 to 0x7F
+    pub front: u8,
+    /// Use the idle and partial porch settings below instead of the normal mode ones
+    pub separate: bool,
+    /// Back porch in idle mode, 0x1 to 0xF
+    pub idle_back: u8,
+    /// Front porch in idle mode, 0x1 to 0xF
+    pub idle_front: u8,
+    /// Back porch in partial mode, 0x1 to 0xF
+    pub partial_back: u8,
+    /// Front porch in partial mode, 0x1 to 0xF
+    pub partial_front: u8,
+}
+
+impl PorchConfig {
+    pub(crate) fn is_valid(&self) -> bool {
+        let normal = 0x01..=0x7F;
+        let other = 0x1..=0xF;
+
+        normal.contains(&self.back)
+            && normal.contains(&self.front)
+            && other.contains(&self.idle_back)
+            && other.contains(&self.idle_front)
+            && other.contains(&self.partial_back)
+            && other.contains(&self.partial_front)
+    }
+
+    pub(crate) fn to_bytes(self) -> [u8; 5] {
+        [
+            self.back,
+            self.front,
+            self.separate as u8,
+            self.idle_back << 4 | self.idle_front,
+            self.partial_back << 4 | self.partial_front,
+        ]
+    }
+}
+
+impl Default for PorchConfig {
+    fn default() -> Self {
+        Self {
+            back: 0x0C,
+            front: 0x0C,
+            separate: false,
+            idle_back: 0x3,
+            idle_front: 0x3,
+            partial_back: 0x3,
+            partial_front: 0x3,
+        }
+    }
+}
+
+///
+/// Panel power settings written to PORCTRL, GCTRL, VCOMS, LCMCTRL,
+/// VDVVRHEN, VRHS, VDVS and PWCTRL1.
+///
+/// The default matches the controller reset values.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PanelPowerConfig {
+    /// Porch settings
+    pub porch: PorchConfig,
+    /// VGH voltage selection (GCTRL VGHS), 0x0 (12.2V) to 0x7 (14.97V)
+    pub vgh: u8,
+    /// VGL voltage selection (GCTRL VGLS), 0x0 (-7.16V) to 0x7 (-12.5V)
+    pub vgl: u8,
+    /// VCOM voltage (VCOMS), 0x00 (0.1V) to 0x3F (1.675V) in 25mV steps
+    pub vcom: u8,
+    /// LCM control bits (LCMCTRL): XMY, XBGR, XINV, XMX, XMH, XMV and XGS
+    pub lcm_control: u8,
+    /// VRH voltage (VRHS), 0x00 (3.55V) to 0x27 (5.5V)
+    pub vrh: u8,
+    /// VDV voltage (VDVS), 0x00 (-0.8V) to 0x3F (0.775V)
+    pub vdv: u8,
+    /// AVDD voltage (PWCTRL1), 0x0 (6.4V) to 0x2 (6.8V)
+    pub avdd: u8,
+    /// AVCL voltage (PWCTRL1), 0x0 (-4.4V) to 0x3 (-5.0V)
+    pub avcl: u8,
+    /// VDS voltage (PWCTRL1), 0x0 (2.19V) to 0x3 (2.61V)
+    pub vds: u8,
+}
+
+impl PanelPowerConfig {
+    pub(crate) fn is_valid(&self) -> bool {
+        self.porch.is_valid()
+            && self.vgh <= 0x7
+            && self.vgl <= 0x7
+            && self.vcom <= 0x3F
+            && self.vrh <= 0x27
+            && self.vdv <= 0x3F
+            && self.avdd <= 0x2
+            && self.avcl <= 0x3
+            && self.vds <= 0x3
+    }
+
+    pub(crate) fn gate_control(&self) -> u8 {
+        self.vgh << 4 | self.vgl
+    }
+
+    pub(crate) fn power_control(&self) -> [u8; 2] {
+        [0xA4, self.avdd << 6 | self.avcl << 4 | self.vds]
+    }
+}
+
+impl Default for PanelPowerConfig {
+    fn default() -> Self {
+        Self {
+            porch: PorchConfig::default(),
+            vgh: 0x3,
+            vgl: 0x5,
+            vcom: 0x20,
+            lcm_control: 0x2C,
+            vrh: 0x0B,
+            vdv: 0x20,
+            avdd: 0x2,
+            avcl: 0x2,
+            vds: 0x1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::Instruction;
+    use crate::mock::display;
+    use crate::{Error, Panel};
+
+    #[test]
+    fn defaults_encode_to_reset_values() {
+        let mut display = display(Panel::default());
+        display
+            .set_power_config(&PanelPowerConfig::default())
+            .unwrap();
+        display.set_frame_rate(FrameRate::default()).unwrap();
+
+        let di = &display.di;
+        assert_eq!(
+            di.data_of(Instruction::PORCTRL as u8),
+            Some(&[0x0C, 0x0C, 0x00, 0x33, 0x33][..])
+        );
+        assert_eq!(di.data_of(Instruction::GCTRL as u8), Some(&[0x35][..]));
+        assert_eq!(
+            di.data_of(Instruction::PWCTRL1 as u8),
+            Some(&[0xA4, 0xA1][..])
+        );
+        assert_eq!(di.data_of(Instruction::FRCTRL2 as u8), Some(&[0x0F][..]));
+    }
+
+    #[test]
+    fn out_of_range_fields_are_rejected() {
+        let mut display = display(Panel::default());
+        let porch = PorchConfig {
+            back: 0x80,
+            ..PorchConfig::default()
+        };
+        let idle_porch = PorchConfig {
+            idle_front: 0x0,
+            ..PorchConfig::default()
+        };
+        let power = PanelPowerConfig {
+            vrh: 0x28,
+            ..PanelPowerConfig::default()
+        };
+        let power_porch = PanelPowerConfig {
+            porch,
+            ..PanelPowerConfig::default()
+        };
+
+        assert!(matches!(
+            display.set_porch(&porch),
+            Err(Error::InvalidArgument)
+        ));
+        assert!(matches!(
+            display.set_porch(&idle_porch),
+            Err(Error::InvalidArgument)
+        ));
+        assert!(matches!(
+            display.set_power_config(&power),
+            Err(Error::InvalidArgument)
+        ));
+        assert!(matches!(
+            display.set_power_config(&power_porch),
+            Err(Error::InvalidArgument)
+        ));
+        assert!(matches!(
+            display.set_frame_rate(FrameRate::new(0x20)),
+            Err(Error::InvalidArgument)
+        ));
+        assert!(display.di.transfers.is_empty());
+    }
+}
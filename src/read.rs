@@ -0,0 +1,670 @@
+//! Reading registers and RAM over read-capable display interfaces.
+
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::pixelcolor::{Rgb565, Rgb666, Rgb888, RgbColor};
+use embedded_hal::blocking::delay::DelayUs;
+
+use crate::instruction::Instruction;
+use crate::{
+    Backlight, BrightnessControl, CabcMode, Error, GammaPreset, InitConfig, MemoryAccessControl,
+    PixelFormat, ResetPin, ST7789,
+};
+
+/// Bytes read from RAM per transfer, a multiple of the 3 bytes per pixel
+const READ_CHUNK: usize = 3 * 32;
+/// Driver ID reported by every ST7789, the other ID bytes can be programmed by the module vendor
+const ST7789_DRIVER_ID: u8 = 0x52;
+
+///
+/// A display interface that can read data back from the controller,
+/// e.g. 3-wire SPI with SDA turnaround or an 8080 parallel bus.
+///
+pub trait ReadDataCommand {
+    ///
+    /// Sends a command and reads the response.
+    /// Implementations discard the dummy read cycle following the command.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - command byte to send
+    /// * `buf` - buffer receiving the response bytes
+    ///
+    fn read_data(&mut self, command: u8, buf: &mut [u8]) -> Result<(), DisplayError>;
+}
+
+///
+/// A color type pixels read from the display RAM can be converted to.
+///
+pub trait ReadColor {
+    ///
+    /// Converts an 18bit color with 6bit channels.
+    ///
+    fn from_rgb666(r: u8, g: u8, b: u8) -> Self;
+}
+
+impl ReadColor for Rgb666 {
+    fn from_rgb666(r: u8, g: u8, b: u8) -> Self {
+        Rgb666::new(r, g, b)
+    }
+}
+
+impl ReadColor for Rgb565 {
+    fn from_rgb666(r: u8, g: u8, b: u8) -> Self {
+        Rgb565::new(r >> 1, g, b >> 1)
+    }
+}
+
+impl ReadColor for Rgb888 {
+    fn from_rgb666(r: u8, g: u8, b: u8) -> Self {
+        Rgb888::new(r << 2 | r >> 4, g << 2 | g >> 4, b << 2 | b >> 4)
+    }
+}
+
+///
+/// Display identification (RDDID).
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DisplayId {
+    /// Module manufacturer ID
+    pub manufacturer: u8,
+    /// Module and driver version ID
+    pub version: u8,
+    /// Module and driver ID
+    pub driver: u8,
+}
+
+///
+/// Decoded display status (RDDST).
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DisplayStatus {
+    /// Booster voltage is on
+    pub booster_on: bool,
+    /// Memory data access control
+    pub madctl: MemoryAccessControl,
+    /// Interface pixel format, `None` for unsupported values
+    pub pixel_format: Option<PixelFormat>,
+    /// Idle mode is on
+    pub idle_mode: bool,
+    /// Partial mode is on
+    pub partial_mode: bool,
+    /// Sleep mode is on
+    pub sleeping: bool,
+    /// Normal display mode is on
+    pub normal_mode: bool,
+    /// Vertical scrolling is on
+    pub scrolling: bool,
+    /// Display inversion is on
+    pub inverted: bool,
+    /// Display output is on
+    pub display_on: bool,
+    /// Tearing effect output is on
+    pub tearing_effect_on: bool,
+    /// Selected gamma curve, `None` for unsupported values
+    pub gamma_preset: Option<GammaPreset>,
+    /// Tearing effect outputs horizontal and vertical blanking
+    pub tearing_horizontal: bool,
+}
+
+impl DisplayStatus {
+    ///
+    /// Decodes the four RDDST status bytes.
+    ///
+    pub fn from_bytes(bytes: [u8; 4]) -> Self {
+        Self {
+            booster_on: bytes[0] & 0b1000_0000 != 0,
+            madctl: MemoryAccessControl::from_byte(bytes[0] << 1),
+            pixel_format: PixelFormat::from_byte(bytes[1] >> 4),
+            idle_mode: bytes[1] & 0b0000_1000 != 0,
+            partial_mode: bytes[1] & 0b0000_0100 != 0,
+            sleeping: bytes[1] & 0b0000_0010 == 0,
+            normal_mode: bytes[1] & 0b0000_0001 != 0,
+            scrolling: bytes[2] & 0b1000_0000 != 0,
+            inverted: bytes[2] & 0b0010_0000 != 0,
+            display_on: bytes[2] & 0b0000_0100 != 0,
+            tearing_effect_on: bytes[2] & 0b0000_0010 != 0,
+            gamma_preset: decode_gamma_preset((bytes[2] & 0b1) << 2 | bytes[3] >> 6),
+            tearing_horizontal: bytes[3] & 0b0010_0000 != 0,
+        }
+    }
+}
+
+///
+/// Decoded display power mode (RDDPM).
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DisplayPowerMode {
+    /// Booster voltage is on
+    pub booster_on: bool,
+    /// Idle mode is on
+    pub idle_mode: bool,
+    /// Partial mode is on
+    pub partial_mode: bool,
+    /// Sleep mode is on
+    pub sleeping: bool,
+    /// Normal display mode is on
+    pub normal_mode: bool,
+    /// Display output is on
+    pub display_on: bool,
+}
+
+impl DisplayPowerMode {
+    ///
+    /// Decodes the RDDPM byte.
+    ///
+    pub fn from_byte(value: u8) -> Self {
+        Self {
+            booster_on: value & 0b1000_0000 != 0,
+            idle_mode: value & 0b0100_0000 != 0,
+            partial_mode: value & 0b0010_0000 != 0,
+            sleeping: value & 0b0001_0000 == 0,
+            normal_mode: value & 0b0000_1000 != 0,
+            display_on: value & 0b0000_0100 != 0,
+        }
+    }
+}
+
+///
+/// Decoded display image mode (RDDIM).
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DisplayImageMode {
+    /// Vertical scrolling is on
+    pub scrolling: bool,
+    /// Display inversion is on
+    pub inverted: bool,
+    /// Selected gamma curve, `None` for unsupported values
+    pub gamma_preset: Option<GammaPreset>,
+}
+
+impl DisplayImageMode {
+    ///
+    /// Decodes the RDDIM byte.
+    ///
+    pub fn from_byte(value: u8) -> Self {
+        Self {
+            scrolling: value & 0b1000_0000 != 0,
+            inverted: value & 0b0010_0000 != 0,
+            gamma_preset: decode_gamma_preset(value & 0b111),
+        }
+    }
+}
+
+///
+/// Decoded display signal mode (RDDSM).
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DisplaySignalMode {
+    /// Tearing effect output is on
+    pub tearing_effect_on: bool,
+    /// Tearing effect outputs horizontal and vertical blanking
+    pub tearing_horizontal: bool,
+    /// Horizontal sync of the RGB interface is on
+    pub horizontal_sync: bool,
+    /// Vertical sync of the RGB interface is on
+    pub vertical_sync: bool,
+    /// Pixel clock of the RGB interface is on
+    pub pixel_clock: bool,
+    /// Data enable of the RGB interface is on
+    pub data_enable: bool,
+}
+
+impl DisplaySignalMode {
+    ///
+    /// Decodes the RDDSM byte.
+    ///
+    pub fn from_byte(value: u8) -> Self {
+        Self {
+            tearing_effect_on: value & 0b1000_0000 != 0,
+            tearing_horizontal: value & 0b0100_0000 != 0,
+            horizontal_sync: value & 0b0010_0000 != 0,
+            vertical_sync: value & 0b0001_0000 != 0,
+            pixel_clock: value & 0b0000_1000 != 0,
+            data_enable: value & 0b0000_0100 != 0,
+        }
+    }
+}
+
+///
+/// Decoded display self-diagnostic result (RDDSDR).
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SelfDiagnostic {
+    /// Register values were loaded correctly after sleep out
+    pub register_loading: bool,
+    /// The display is functioning after sleep out
+    pub functionality: bool,
+}
+
+impl SelfDiagnostic {
+    ///
+    /// Decodes the RDDSDR byte.
+    ///
+    pub fn from_byte(value: u8) -> Self {
+        Self {
+            register_loading: value & 0b1000_0000 != 0,
+            functionality: value & 0b0100_0000 != 0,
+        }
+    }
+}
+
+impl<DI, RST, BL, C, RstE, BlE> ST7789<DI, RST, BL, C>
+where
+    DI: WriteOnlyDataCommand + ReadDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+{
+    ///
+    /// Reads the display identification
+    ///
+    pub fn read_id(&mut self) -> Result<DisplayId, Error<RstE, BlE>> {
+        let mut id = [0; 3];
+        self.read_data(Instruction::RDDID, &mut id)?;
+
+        Ok(DisplayId {
+            manufacturer: id[0],
+            version: id[1],
+            driver: id[2],
+        })
+    }
+
+    ///
+    /// Reads and decodes the display status
+    ///
+    pub fn read_status(&mut self) -> Result<DisplayStatus, Error<RstE, BlE>> {
+        let mut status = [0; 4];
+        self.read_data(Instruction::RDDST, &mut status)?;
+
+        Ok(DisplayStatus::from_bytes(status))
+    }
+
+    ///
+    /// Reads and decodes the display power mode
+    ///
+    pub fn read_power_mode(&mut self) -> Result<DisplayPowerMode, Error<RstE, BlE>> {
+        let value = self.read_byte(Instruction::RDDPM)?;
+        Ok(DisplayPowerMode::from_byte(value))
+    }
+
+    ///
+    /// Reads the memory access control register
+    ///
+    pub fn read_memory_access_control(&mut self) -> Result<MemoryAccessControl, Error<RstE, BlE>> {
+        let value = self.read_byte(Instruction::RDDMADCTL)?;
+        Ok(MemoryAccessControl::from_byte(value))
+    }
+
+    ///
+    /// Reads the interface pixel format, `None` for formats not supported by the driver
+    ///
+    pub fn read_pixel_format(&mut self) -> Result<Option<PixelFormat>, Error<RstE, BlE>> {
+        let value = self.read_byte(Instruction::RDDCOLMOD)?;
+        Ok(PixelFormat::from_byte(value))
+    }
+
+    ///
+    /// Reads and decodes the display image mode
+    ///
+    pub fn read_image_mode(&mut self) -> Result<DisplayImageMode, Error<RstE, BlE>> {
+        let value = self.read_byte(Instruction::RDDIM)?;
+        Ok(DisplayImageMode::from_byte(value))
+    }
+
+    ///
+    /// Reads and decodes the display signal mode
+    ///
+    pub fn read_signal_mode(&mut self) -> Result<DisplaySignalMode, Error<RstE, BlE>> {
+        let value = self.read_byte(Instruction::RDDSM)?;
+        Ok(DisplaySignalMode::from_byte(value))
+    }
+
+    ///
+    /// Reads the self-diagnostic result of the last sleep out
+    ///
+    pub fn read_self_diagnostic(&mut self) -> Result<SelfDiagnostic, Error<RstE, BlE>> {
+        let value = self.read_byte(Instruction::RDDSDR)?;
+        Ok(SelfDiagnostic::from_byte(value))
+    }
+
+    ///
+    /// Reads the display brightness, see `set_display_brightness`
+    ///
+    pub fn read_display_brightness(&mut self) -> Result<u8, Error<RstE, BlE>> {
+        self.read_byte(Instruction::RDDISBV)
+    }
+
+    ///
+    /// Reads the brightness control settings, see `set_brightness_control`
+    ///
+    pub fn read_brightness_control(&mut self) -> Result<BrightnessControl, Error<RstE, BlE>> {
+        let value = self.read_byte(Instruction::RDCTRLD)?;
+        Ok(BrightnessControl::from_byte(value))
+    }
+
+    ///
+    /// Reads the content adaptive brightness control mode, see `set_adaptive_brightness`
+    ///
+    pub fn read_adaptive_brightness(&mut self) -> Result<CabcMode, Error<RstE, BlE>> {
+        let value = self.read_byte(Instruction::RDCABC)?;
+        Ok(CabcMode::from_byte(value))
+    }
+
+    ///
+    /// Reads the CABC minimum brightness, see `set_cabc_min_brightness`
+    ///
+    pub fn read_cabc_min_brightness(&mut self) -> Result<u8, Error<RstE, BlE>> {
+        self.read_byte(Instruction::RDCABCMB)
+    }
+
+    ///
+    /// Reads the display identification through the separate RDID1, RDID2 and RDID3 registers
+    ///
+    pub fn read_id_registers(&mut self) -> Result<DisplayId, Error<RstE, BlE>> {
+        Ok(DisplayId {
+            manufacturer: self.read_byte(Instruction::RDID1)?,
+            version: self.read_byte(Instruction::RDID2)?,
+            driver: self.read_byte(Instruction::RDID3)?,
+        })
+    }
+
+    ///
+    /// Checks that an ST7789 is attached and responding by reading its ID and status.
+    /// Returns `Error::BusFloating` if the reads return only 0x00 or 0xFF and
+    /// `Error::UnexpectedId` if the ID doesn't match.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected` - ID programmed by the module vendor, `None` only checks the ST7789 driver ID
+    ///
+    pub fn verify(
+        &mut self,
+        expected: Option<DisplayId>,
+    ) -> Result<DisplayStatus, Error<RstE, BlE>> {
+        let id = self.read_id()?;
+        let status = self.read_status()?;
+
+        // a floating bus decodes to the status of all 0x00 or 0xFF bytes
+        if is_floating(&[id.manufacturer, id.version, id.driver])
+            || status == DisplayStatus::from_bytes([0x00; 4])
+            || status == DisplayStatus::from_bytes([0xFF; 4])
+        {
+            return Err(Error::BusFloating);
+        }
+        let matches = match expected {
+            Some(expected) => id == expected,
+            None => id.driver == ST7789_DRIVER_ID,
+        };
+        if !matches {
+            return Err(Error::UnexpectedId(id));
+        }
+
+        Ok(status)
+    }
+
+    ///
+    /// Runs commands to initialize the display with the given configuration,
+    /// then checks the display is responding, see `verify`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - initialization settings
+    /// * `expected` - ID programmed by the module vendor, `None` only checks the ST7789 driver ID
+    /// * `delay_source` - mutable reference to a delay provider
+    ///
+    pub fn init_verified(
+        &mut self,
+        config: &InitConfig,
+        expected: Option<DisplayId>,
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Result<DisplayStatus, Error<RstE, BlE>> {
+        self.init_with(config, delay_source)?;
+        self.verify(expected)
+    }
+
+    ///
+    /// Reads pixel colors in given rectangle bounds from the display RAM.
+    /// Returns `Error::InvalidArgument` if the bounds are reversed or `pixels` is too short.
+    ///
+    /// # Arguments
+    ///
+    /// * `sx` - x coordinate start
+    /// * `sy` - y coordinate start
+    /// * `ex` - x coordinate end
+    /// * `ey` - y coordinate end
+    /// * `pixels` - buffer receiving the colors row by row, e.g. `Rgb565` or `Rgb666`
+    ///
+    pub fn read_pixels<P>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        pixels: &mut [P],
+    ) -> Result<(), Error<RstE, BlE>>
+    where
+        P: ReadColor,
+    {
+        if sx > ex || sy > ey {
+            return Err(Error::InvalidArgument);
+        }
+        let count = usize::from(ex - sx + 1) * usize::from(ey - sy + 1);
+        let mut pixels = pixels
+            .get_mut(..count)
+            .ok_or(Error::InvalidArgument)?
+            .iter_mut();
+
+        self.read_pixels_with(sx, sy, ex, ey, |color| {
+            if let Some(pixel) = pixels.next() {
+                *pixel = color;
+            }
+        })
+    }
+
+    ///
+    /// Streams pixel colors in given rectangle bounds from the display RAM to a callback.
+    /// Pixels are always read in the 18bit format, regardless of the interface pixel format,
+    /// and are converted to the color type of the callback.
+    /// Returns `Error::InvalidArgument` if the bounds are reversed.
+    ///
+    /// # Arguments
+    ///
+    /// * `sx` - x coordinate start
+    /// * `sy` - y coordinate start
+    /// * `ex` - x coordinate end
+    /// * `ey` - y coordinate end
+    /// * `f` - called with each color row by row
+    ///
+    pub fn read_pixels_with<P, F>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        mut f: F,
+    ) -> Result<(), Error<RstE, BlE>>
+    where
+        P: ReadColor,
+        F: FnMut(P),
+    {
+        if sx > ex || sy > ey {
+            return Err(Error::InvalidArgument);
+        }
+        let mut remaining = usize::from(ex - sx + 1) * usize::from(ey - sy + 1);
+
+        self.set_address_window(sx, sy, ex, ey)?;
+        let mut buf = [0; READ_CHUNK];
+        let mut command = Instruction::RAMRD;
+        while remaining > 0 {
+            let len = remaining.min(READ_CHUNK / 3);
+            let bytes = &mut buf[..len * 3];
+            self.read_data(command, bytes)?;
+            for rgb in bytes.chunks_exact(3) {
+                f(P::from_rgb666(rgb[0] >> 2, rgb[1] >> 2, rgb[2] >> 2));
+            }
+            remaining -= len;
+            command = Instruction::RAMRDC;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Streams the display RAM of the visible area to a callback row by row,
+    /// see `read_pixels_with`. The hardware scroll offset is not applied.
+    ///
+    pub fn screenshot<P, F>(&mut self, f: F) -> Result<(), Error<RstE, BlE>>
+    where
+        P: ReadColor,
+        F: FnMut(P),
+    {
+        let (width, height) = self.panel.size(self.madctl);
+        self.read_pixels_with(0, 0, width - 1, height - 1, f)
+    }
+
+    fn read_data(&mut self, command: Instruction, buf: &mut [u8]) -> Result<(), Error<RstE, BlE>> {
+        self.di
+            .read_data(command as u8, buf)
+            .map_err(|_| Error::DisplayError)
+    }
+
+    fn read_byte(&mut self, command: Instruction) -> Result<u8, Error<RstE, BlE>> {
+        let mut value = [0];
+        self.read_data(command, &mut value)?;
+        Ok(value[0])
+    }
+}
+
+// Decodes the 3bit gamma curve selection.
+fn decode_gamma_preset(bits: u8) -> Option<GammaPreset> {
+    match bits {
+        0 => Some(GammaPreset::G2_2),
+        1 => Some(GammaPreset::G1_8),
+        2 => Some(GammaPreset::G2_5),
+        3 => Some(GammaPreset::G1_0),
+        _ => None,
+    }
+}
+
+// True if the bytes look like an undriven bus.
+fn is_floating(bytes: &[u8]) -> bool {
+    bytes.iter().all(|&b| b == 0x00) || bytes.iter().all(|&b| b == 0xFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{display, Transfer};
+    use crate::{ColorOrder, Orientation, Panel};
+
+    // RAM contents of 40 pixels with the 6bit channels r = i, g = 63 - i and b = 63.
+    fn ram_bytes() -> impl Iterator<Item = u8> {
+        (0..40).flat_map(|i: u8| [i << 2, (63 - i) << 2, 0xFC])
+    }
+
+    #[test]
+    fn read_pixels_continues_after_first_chunk() {
+        let mut display = display(Panel::default());
+        display.di.responses = ram_bytes().collect();
+
+        let mut count = 0;
+        display
+            .read_pixels_with(0, 0, 4, 7, |color: Rgb565| {
+                let i = count as u8;
+                assert_eq!(color, Rgb565::new(i >> 1, 63 - i, 31));
+                count += 1;
+            })
+            .unwrap();
+
+        assert_eq!(count, 40);
+        assert_eq!(
+            display.di.transfers,
+            [
+                Transfer::Command(Instruction::CASET as u8),
+                Transfer::Data([0, 0, 0, 4].into()),
+                Transfer::Command(Instruction::RASET as u8),
+                Transfer::Data([0, 0, 0, 7].into()),
+                Transfer::Read(Instruction::RAMRD as u8, 96),
+                Transfer::Read(Instruction::RAMRDC as u8, 24),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_pixels_converts_colors() {
+        let mut display = display(Panel::default());
+        display.di.responses = ram_bytes().collect();
+        let mut pixels = [Rgb888::BLACK; 40];
+        display.read_pixels(0, 0, 39, 0, &mut pixels).unwrap();
+
+        assert_eq!(pixels[0], Rgb888::new(0x00, 0xFF, 0xFF));
+        assert_eq!(pixels[33], Rgb888::new(0x86, 0x79, 0xFF));
+        assert_eq!(Rgb666::from_rgb666(1, 2, 3), Rgb666::new(1, 2, 3));
+        assert_eq!(Rgb565::from_rgb666(63, 63, 63), Rgb565::WHITE);
+        assert_eq!(Rgb888::from_rgb666(63, 63, 63), Rgb888::WHITE);
+    }
+
+    #[test]
+    fn status_decodes_all_fields() {
+        let status = DisplayStatus::from_bytes([0xB4, 0x53, 0x26, 0x60]);
+
+        assert_eq!(
+            status,
+            DisplayStatus {
+                booster_on: true,
+                madctl: MemoryAccessControl::from(Orientation::Landscape)
+                    .with_color_order(ColorOrder::Bgr),
+                pixel_format: Some(PixelFormat::Rgb565),
+                idle_mode: false,
+                partial_mode: false,
+                sleeping: false,
+                normal_mode: true,
+                scrolling: false,
+                inverted: true,
+                display_on: true,
+                tearing_effect_on: true,
+                gamma_preset: Some(GammaPreset::G1_8),
+                tearing_horizontal: true,
+            }
+        );
+    }
+
+    #[test]
+    fn status_after_reset() {
+        let status = DisplayStatus::from_bytes([0x00, 0x60, 0x00, 0x00]);
+
+        assert!(status.sleeping);
+        assert!(!status.display_on);
+        assert_eq!(status.madctl, MemoryAccessControl::default());
+        assert_eq!(status.pixel_format, Some(PixelFormat::Rgb666));
+        assert_eq!(status.gamma_preset, Some(GammaPreset::G2_2));
+    }
+
+    #[test]
+    fn status_gamma_spans_bytes() {
+        let status = DisplayStatus::from_bytes([0x00, 0x00, 0x00, 0xC0]);
+        assert_eq!(status.gamma_preset, Some(GammaPreset::G1_0));
+
+        let status = DisplayStatus::from_bytes([0x00, 0x00, 0x01, 0x00]);
+        assert_eq!(status.gamma_preset, None);
+        assert_eq!(status.pixel_format, None);
+    }
+
+    #[test]
+    fn power_mode_decodes_sleep_out() {
+        let mode = DisplayPowerMode::from_byte(0x9C);
+
+        assert!(mode.booster_on);
+        assert!(!mode.sleeping);
+        assert!(mode.normal_mode);
+        assert!(mode.display_on);
+        assert!(DisplayPowerMode::from_byte(0x08).sleeping);
+    }
+
+    #[test]
+    fn floating_bus() {
+        assert!(is_floating(&[0x00, 0x00, 0x00]));
+        assert!(is_floating(&[0xFF, 0xFF, 0xFF]));
+        assert!(!is_floating(&[0x85, 0x85, 0x52]));
+    }
+}
@@ -0,0 +1,273 @@
+//! Vertical scrolling.
+
+use crate::{Backlight, Error, Panel, ResetPin, ST7789};
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::RgbColor;
+
+///
+/// Vertical scroll area definition (VSCRDER) in controller RAM rows.
+///
+/// The three areas must add up to the RAM height of the panel.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ScrollArea {
+    /// Rows fixed at the top
+    pub top_fixed: u16,
+    /// Rows in the scrolling area
+    pub scroll: u16,
+    /// Rows fixed at the bottom
+    pub bottom_fixed: u16,
+}
+
+impl ScrollArea {
+    ///
+    /// Creates a scroll area definition
+    ///
+    /// # Arguments
+    ///
+    /// * `top_fixed` - number of fixed rows at the top
+    /// * `scroll` - number of rows in the scrolling area
+    /// * `bottom_fixed` - number of fixed rows at the bottom
+    ///
+    pub const fn new(top_fixed: u16, scroll: u16, bottom_fixed: u16) -> Self {
+        Self {
+            top_fixed,
+            scroll,
+            bottom_fixed,
+        }
+    }
+
+    ///
+    /// Creates a scroll area within the visible rows of the panel, keeping
+    /// `top` rows fixed at the top and `bottom` rows fixed at the bottom.
+    /// Returns `None` if no rows would be left to scroll.
+    ///
+    pub fn with_margins(panel: &Panel, top: u16, bottom: u16) -> Option<Self> {
+        let scroll = panel.height.checked_sub(top)?.checked_sub(bottom)?;
+        if scroll == 0 {
+            return None;
+        }
+
+        let top_fixed = panel.offset_y.checked_add(top)?;
+        let bottom_fixed = panel
+            .ram_height
+            .checked_sub(top_fixed)?
+            .checked_sub(scroll)?;
+
+        Some(Self::new(top_fixed, scroll, bottom_fixed))
+    }
+
+    /// Scroll area covering the visible rows of the given panel,
+    /// not valid for panels that don't fit the RAM.
+    pub(crate) fn for_panel(panel: &Panel) -> Self {
+        Self::new(
+            panel.offset_y,
+            panel.height,
+            panel
+                .ram_height
+                .saturating_sub(panel.height)
+                .saturating_sub(panel.offset_y),
+        )
+    }
+
+    /// True if the areas cover exactly the RAM rows of the panel.
+    pub(crate) fn is_valid(&self, panel: &Panel) -> bool {
+        let total =
+            u32::from(self.top_fixed) + u32::from(self.scroll) + u32::from(self.bottom_fixed);
+
+        self.scroll > 0 && total == u32::from(panel.ram_height)
+    }
+}
+
+///
+/// Screen axis moved by hardware scrolling in the current orientation.
+///
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScrollAxis {
+    /// Content moves along the y axis
+    Vertical,
+    /// Content moves along the x axis
+    Horizontal,
+}
+
+impl ScrollArea {
+    /// RAM row displayed at the given panel row for a scroll offset.
+    pub(crate) fn source_row(&self, row: u16, offset: u16) -> u16 {
+        if row < self.top_fixed || row >= self.top_fixed + self.scroll {
+            return row;
+        }
+
+        let scrolled =
+            (u32::from(row - self.top_fixed) + u32::from(offset)) % u32::from(self.scroll);
+        self.top_fixed + scrolled as u16
+    }
+}
+
+impl<DI, RST, BL, C, RstE, BlE> ST7789<DI, RST, BL, C>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+{
+    ///
+    /// Returns the screen axis moved by hardware scrolling. The controller always
+    /// scrolls along the RAM rows, which run horizontally in landscape orientations.
+    ///
+    pub fn scroll_axis(&self) -> ScrollAxis {
+        if self.madctl.swap_xy {
+            ScrollAxis::Horizontal
+        } else {
+            ScrollAxis::Vertical
+        }
+    }
+
+    ///
+    /// Returns the current scroll offset relative to the scroll area, in RAM row direction
+    ///
+    pub fn scroll_offset(&self) -> u16 {
+        self.scroll_offset
+    }
+
+    ///
+    /// Returns the current scroll offset in screen direction, see `set_screen_scroll_offset`
+    ///
+    pub fn screen_scroll_offset(&self) -> u16 {
+        self.to_screen_offset(self.scroll_offset)
+    }
+
+    ///
+    /// Scrolls the content towards lower screen coordinates along `scroll_axis`,
+    /// taking mirrored row order of the current orientation into account.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - scroll offset in pixels, relative to the start of the scroll area
+    ///
+    pub fn set_screen_scroll_offset(&mut self, offset: u16) -> Result<(), Error<RstE, BlE>> {
+        let offset = self.to_screen_offset(offset % self.scroll_area.scroll);
+        self.set_scroll_offset(offset)
+    }
+
+    ///
+    /// Maps a screen position, as currently seen with scrolling applied, to the drawing
+    /// coordinates that end up there in the scrolled RAM ring. Positions in the fixed
+    /// areas are returned unchanged.
+    ///
+    /// Returns `None` if the position lies outside the visible area or maps to RAM rows
+    /// that are not addressable by drawing coordinates.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - screen x coordinate
+    /// * `y` - screen y coordinate
+    ///
+    pub fn map_scrolled(&self, x: u16, y: u16) -> Option<(u16, u16)> {
+        let (size_x, size_y) = self.panel.size(self.madctl);
+        if x >= size_x || y >= size_y {
+            return None;
+        }
+
+        let (offset_x, offset_y) = self.panel.offset(self.madctl);
+        let (along, axis_offset) = if self.madctl.swap_xy {
+            (x, offset_x)
+        } else {
+            (y, offset_y)
+        };
+
+        let ram_row = self.physical_row(along + axis_offset);
+        let source = self.scroll_area.source_row(ram_row, self.scroll_offset);
+        let mapped = self.physical_row(source).checked_sub(axis_offset)?;
+
+        if self.madctl.swap_xy {
+            Some((mapped, y))
+        } else {
+            Some((x, mapped))
+        }
+    }
+
+    // Converts between RAM row direction and screen direction offsets.
+    fn to_screen_offset(&self, offset: u16) -> u16 {
+        if self.madctl.mirror_y && offset > 0 {
+            self.scroll_area.scroll - offset
+        } else {
+            offset
+        }
+    }
+
+    // Converts between an address along the scroll axis and a RAM row, both ways.
+    fn physical_row(&self, address: u16) -> u16 {
+        if self.madctl.mirror_y {
+            self.panel.ram_height - 1 - address
+        } else {
+            address
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::display;
+    use crate::Orientation;
+
+    #[test]
+    fn source_row_wraps_within_scroll_area() {
+        let area = ScrollArea::new(10, 100, 210);
+
+        assert_eq!(area.source_row(5, 30), 5);
+        assert_eq!(area.source_row(10, 30), 40);
+        assert_eq!(area.source_row(100, 30), 30);
+        assert_eq!(area.source_row(110, 30), 110);
+    }
+
+    #[test]
+    fn margins_follow_panel_offset() {
+        let panel = Panel::WAVESHARE_1_69;
+
+        assert_eq!(
+            ScrollArea::with_margins(&panel, 20, 20),
+            Some(ScrollArea::new(40, 240, 40))
+        );
+        assert_eq!(ScrollArea::with_margins(&panel, 140, 140), None);
+    }
+
+    #[test]
+    fn screen_offset_maps_along_scroll_axis() {
+        let orientations = [
+            (Orientation::Portrait, (0, 10)),
+            (Orientation::Landscape, (10, 0)),
+            (Orientation::PortraitSwapped, (0, 10)),
+            (Orientation::LandscapeSwapped, (10, 0)),
+        ];
+
+        for &(orientation, expected) in orientations.iter() {
+            let mut display = display(Panel::default());
+            display.set_orientation(orientation).unwrap();
+            display.set_screen_scroll_offset(10).unwrap();
+
+            assert_eq!(display.screen_scroll_offset(), 10);
+            assert_eq!(display.map_scrolled(0, 0), Some(expected));
+        }
+    }
+
+    #[test]
+    fn mirrored_mapping_keeps_fixed_rows() {
+        let panel = Panel::WAVESHARE_1_69;
+        let mut display = display(panel);
+        display
+            .set_orientation(Orientation::PortraitSwapped)
+            .unwrap();
+        display
+            .set_scroll_area(ScrollArea::with_margins(&panel, 20, 20).unwrap())
+            .unwrap();
+        display.set_screen_scroll_offset(10).unwrap();
+
+        assert_eq!(display.scroll_offset(), 230);
+        assert_eq!(display.map_scrolled(0, 0), Some((0, 0)));
+        assert_eq!(display.map_scrolled(0, 279), Some((0, 279)));
+        assert_eq!(display.map_scrolled(0, 100), Some((0, 110)));
+        assert_eq!(display.map_scrolled(0, 255), Some((0, 25)));
+        assert_eq!(display.map_scrolled(0, 280), None);
+    }
+}
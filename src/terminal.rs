@@ -0,0 +1,357 @@
+//! Scrolling text terminal built on hardware scrolling.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::RgbColor;
+
+use crate::font::{glyph, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::private::Token;
+use crate::{Backlight, DisplayDriver, Error, ResetPin, ScrollArea, ScrollAxis, ST7789};
+
+/// Character cell width including spacing
+const CELL_WIDTH: u16 = GLYPH_WIDTH + 1;
+/// Character cell height including spacing
+const CELL_HEIGHT: u16 = GLYPH_HEIGHT + 1;
+/// Max number of parameters in an escape sequence
+const MAX_PARAMS: usize = 4;
+
+// Escape sequence parser state
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Parser {
+    Text,
+    Escape,
+    Csi,
+}
+
+///
+/// Text terminal drawing a 5x7 pixel font into the scroll area of the display.
+///
+/// New lines are appended by scrolling the hardware scroll area one text row and
+/// drawing only the newly exposed row. The rows above and below the text rows are
+/// fixed and can be drawn to through `display`. The terminal borrows either an
+/// `ST7789` or a ready `typestate::Driver`, see `DisplayDriver`.
+/// Supports `\n`, `\r` and the ANSI SGR color sequences (`ESC[...m`).
+///
+pub struct Terminal<'a, DI, RST, BL, C, D = ST7789<DI, RST, BL, C>>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin,
+    BL: Backlight,
+    C: RgbColor,
+    D: DisplayDriver<DI, RST, BL, C>,
+{
+    display: &'a mut D,
+    // First screen row of the text area
+    top: u16,
+    // Width of the text area in pixels
+    width: u16,
+    // Text area size in characters
+    columns: u16,
+    rows: u16,
+    // Cursor position
+    column: u16,
+    row: u16,
+    // Text rows scrolled so far, modulo `rows`
+    scrolled: u16,
+    // Colors
+    default_foreground: C,
+    default_background: C,
+    foreground: C,
+    background: C,
+    // Escape sequence parser
+    parser: Parser,
+    params: [u16; MAX_PARAMS],
+    param_index: usize,
+
+    _phantom: PhantomData<(DI, RST, BL)>,
+}
+
+impl<'a, DI, RST, BL, C, D, RstE, BlE> Terminal<'a, DI, RST, BL, C, D>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+    D: DisplayDriver<DI, RST, BL, C>,
+{
+    ///
+    /// Creates a terminal between a fixed header and footer and clears its text area.
+    /// The display scroll area is redefined to cover the text rows, rows that don't
+    /// fit a whole text row are added to the footer.
+    ///
+    /// Returns `Error::InvalidArgument` if the display scrolls horizontally in the
+    /// current orientation or no text row fits between header and footer.
+    ///
+    /// # Arguments
+    ///
+    /// * `display` - the display to draw to
+    /// * `header` - fixed rows at the top of the screen
+    /// * `footer` - fixed rows at the bottom of the screen
+    /// * `foreground` - default text color
+    /// * `background` - default background color
+    ///
+    pub fn new(
+        display: &'a mut D,
+        header: u16,
+        footer: u16,
+        foreground: C,
+        background: C,
+    ) -> Result<Self, Error<RstE, BlE>> {
+        let driver = display.driver(Token(()));
+        if driver.scroll_axis() != ScrollAxis::Vertical {
+            return Err(Error::InvalidArgument);
+        }
+
+        let (width, height) = driver.panel.size(driver.madctl);
+        let available = height
+            .checked_sub(header)
+            .and_then(|rows| rows.checked_sub(footer))
+            .ok_or(Error::InvalidArgument)?;
+        let rows = available / CELL_HEIGHT;
+        let columns = width / CELL_WIDTH;
+        if rows == 0 || columns == 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        // the scroll area is defined in RAM rows, which run bottom to top when mirrored
+        let footer = height - header - rows * CELL_HEIGHT;
+        let (top_fixed, bottom_fixed) = if driver.madctl.mirror_y {
+            (footer, header)
+        } else {
+            (header, footer)
+        };
+        let area = ScrollArea::with_margins(&driver.panel, top_fixed, bottom_fixed)
+            .ok_or(Error::InvalidArgument)?;
+        driver.set_scroll_area(area)?;
+
+        let mut terminal = Self {
+            display,
+            top: header,
+            width,
+            columns,
+            rows,
+            column: 0,
+            row: 0,
+            scrolled: 0,
+            default_foreground: foreground,
+            default_background: background,
+            foreground,
+            background,
+            parser: Parser::Text,
+            params: [0; MAX_PARAMS],
+            param_index: 0,
+            _phantom: PhantomData,
+        };
+        terminal.clear()?;
+
+        Ok(terminal)
+    }
+
+    ///
+    /// Returns the display for drawing into the fixed header and footer
+    ///
+    pub fn display(&mut self) -> &mut D {
+        self.display
+    }
+
+    ///
+    /// Returns the number of text columns
+    ///
+    pub fn columns(&self) -> u16 {
+        self.columns
+    }
+
+    ///
+    /// Returns the number of text rows
+    ///
+    pub fn rows(&self) -> u16 {
+        self.rows
+    }
+
+    ///
+    /// Sets the colors used for following text
+    ///
+    pub fn set_colors(&mut self, foreground: C, background: C) {
+        self.foreground = foreground;
+        self.background = background;
+    }
+
+    ///
+    /// Clears the text area, resets scrolling and moves the cursor to the top left
+    ///
+    pub fn clear(&mut self) -> Result<(), Error<RstE, BlE>> {
+        self.display.driver(Token(())).set_screen_scroll_offset(0)?;
+        self.scrolled = 0;
+        self.column = 0;
+        self.row = 0;
+
+        let count = u32::from(self.width) * u32::from(self.rows * CELL_HEIGHT);
+        let background = self.background;
+        self.display.driver(Token(())).set_pixels(
+            0,
+            self.top,
+            self.width - 1,
+            self.top + self.rows * CELL_HEIGHT - 1,
+            (0..count).map(|_| background),
+        )
+    }
+
+    ///
+    /// Writes a single character, interpreting control characters and escape sequences
+    ///
+    pub fn write_char(&mut self, c: char) -> Result<(), Error<RstE, BlE>> {
+        match self.parser {
+            Parser::Text => match c {
+                '\x1b' => self.parser = Parser::Escape,
+                '\n' => self.new_line()?,
+                '\r' => self.column = 0,
+                _ => self.put_char(c)?,
+            },
+            Parser::Escape => {
+                if c == '[' {
+                    self.params = [0; MAX_PARAMS];
+                    self.param_index = 0;
+                    self.parser = Parser::Csi;
+                } else {
+                    self.parser = Parser::Text;
+                }
+            }
+            Parser::Csi => match c {
+                '0'..='9' => {
+                    if let Some(param) = self.params.get_mut(self.param_index) {
+                        let digit = c as u16 - '0' as u16;
+                        *param = param.saturating_mul(10).saturating_add(digit);
+                    }
+                }
+                ';' => self.param_index += 1,
+                'm' => {
+                    self.select_graphic_rendition();
+                    self.parser = Parser::Text;
+                }
+                '\x40'..='\x7e' => self.parser = Parser::Text, // unsupported sequence
+                _ => {}
+            },
+        }
+
+        Ok(())
+    }
+
+    // Applies the parameters of an SGR sequence.
+    fn select_graphic_rendition(&mut self) {
+        let count = (self.param_index + 1).min(MAX_PARAMS);
+
+        for param in self.params[..count].iter().copied() {
+            match param {
+                0 => {
+                    self.foreground = self.default_foreground;
+                    self.background = self.default_background;
+                }
+                p @ 30..=37 => self.foreground = ansi_color(p - 30),
+                39 => self.foreground = self.default_foreground,
+                p @ 40..=47 => self.background = ansi_color(p - 40),
+                49 => self.background = self.default_background,
+                p @ 90..=97 => self.foreground = ansi_color(p - 90),
+                p @ 100..=107 => self.background = ansi_color(p - 100),
+                _ => {}
+            }
+        }
+    }
+
+    // Draws a printable character at the cursor, wrapping to a new line when needed.
+    fn put_char(&mut self, c: char) -> Result<(), Error<RstE, BlE>> {
+        if self.column == self.columns {
+            self.new_line()?;
+        }
+
+        let (x, y) = self.cell_origin(self.row, self.column * CELL_WIDTH)?;
+        let columns = glyph(c);
+        let (foreground, background) = (self.foreground, self.background);
+        let colors = (0..CELL_WIDTH * CELL_HEIGHT).map(|i| {
+            let (col, row) = (i % CELL_WIDTH, i / CELL_WIDTH);
+            let set = col < GLYPH_WIDTH && (columns[usize::from(col)] >> row) & 1 != 0;
+            if set {
+                foreground
+            } else {
+                background
+            }
+        });
+
+        self.display.driver(Token(())).set_pixels(
+            x,
+            y,
+            x + CELL_WIDTH - 1,
+            y + CELL_HEIGHT - 1,
+            colors,
+        )?;
+        self.column += 1;
+        Ok(())
+    }
+
+    // Moves the cursor to the start of the next line, scrolling when at the bottom.
+    fn new_line(&mut self) -> Result<(), Error<RstE, BlE>> {
+        self.column = 0;
+        if self.row + 1 < self.rows {
+            self.row += 1;
+            return Ok(());
+        }
+
+        self.scrolled = (self.scrolled + 1) % self.rows;
+        self.display
+            .driver(Token(()))
+            .set_screen_scroll_offset(self.scrolled * CELL_HEIGHT)?;
+
+        // the exposed row still shows the previous top row
+        let (x, y) = self.cell_origin(self.row, 0)?;
+        let count = u32::from(self.width - x) * u32::from(CELL_HEIGHT);
+        let background = self.background;
+        self.display.driver(Token(())).set_pixels(
+            x,
+            y,
+            self.width - 1,
+            y + CELL_HEIGHT - 1,
+            (0..count).map(|_| background),
+        )
+    }
+
+    // Drawing coordinates of a text row at the given x position in the scrolled ring.
+    fn cell_origin(&mut self, row: u16, x: u16) -> Result<(u16, u16), Error<RstE, BlE>> {
+        let top = self.top;
+        self.display
+            .driver(Token(()))
+            .map_scrolled(x, top + row * CELL_HEIGHT)
+            .ok_or(Error::InvalidArgument)
+    }
+}
+
+impl<'a, DI, RST, BL, C, D, RstE, BlE> fmt::Write for Terminal<'a, DI, RST, BL, C, D>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+    D: DisplayDriver<DI, RST, BL, C>,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            self.write_char(c).map_err(|_| fmt::Error)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Standard ANSI colors 0 to 7.
+fn ansi_color<C: RgbColor>(index: u16) -> C {
+    match index {
+        0 => C::BLACK,
+        1 => C::RED,
+        2 => C::GREEN,
+        3 => C::YELLOW,
+        4 => C::BLUE,
+        5 => C::MAGENTA,
+        6 => C::CYAN,
+        _ => C::WHITE,
+    }
+}
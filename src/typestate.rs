@@ -0,0 +1,787 @@
+//! Typestate driver lifecycle.
+//!
+//! `Driver` wraps `ST7789` and tracks its lifecycle in the type system, so drawing
+//! is only possible once the display is initialized and awake:
+//!
+//! `Driver<_, Uninitialized>` --`init`--> `Driver<_, Ready>` --`sleep`--> `Driver<_, Sleeping>` --`wake`--> `Driver<_, Ready>`
+//!
+//! Failed transitions return the driver in its previous state together with the error.
+//! Use `ST7789` directly for the dynamic API.
+
+use core::marker::PhantomData;
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::pixelcolor::RgbColor;
+use embedded_hal::blocking::delay::DelayUs;
+
+use crate::instruction::Instruction;
+use crate::private::{Borrow, Token};
+use crate::{
+    Backlight, BacklightState, BrightnessControl, CabcMode, DisplayId, DisplayImageMode,
+    DisplayPowerMode, DisplaySignalMode, DisplayStatus, Error, FrameRate, GammaCurve, GammaPreset,
+    InitCommand, InitConfig, MemoryAccessControl, Orientation, Panel, PanelPowerConfig,
+    PixelFormat, PorchConfig, PowerState, ReadColor, ReadDataCommand, ResetPin, ScrollArea,
+    ScrollAxis, SelfDiagnostic, TearingEffect, ST7789,
+};
+
+/// Result of a state transition, returning the driver in its previous state on failure
+pub type Transition<Next, Previous, RstE, BlE> = Result<Next, (Previous, Error<RstE, BlE>)>;
+
+/// Created but not initialized yet
+pub struct Uninitialized;
+
+/// Initialized and awake, drawing is possible
+pub struct Ready;
+
+/// In sleep mode
+pub struct Sleeping;
+
+///
+/// ST7789 driver with its lifecycle state `S` tracked in the type.
+///
+pub struct Driver<DI, RST, BL, C, S>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin,
+    BL: Backlight,
+    C: RgbColor,
+{
+    display: ST7789<DI, RST, BL, C>,
+    _state: PhantomData<S>,
+}
+
+impl<DI, RST, BL, C, S, RstE, BlE> Driver<DI, RST, BL, C, S>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+{
+    ///
+    /// Release resources allocated to this driver back.
+    /// This returns the display interface, the RST pin and the backlight pin deconstructing the driver.
+    ///
+    pub fn release(self) -> (DI, RST, BL) {
+        self.display.release()
+    }
+
+    ///
+    /// Returns the panel description this driver was created with
+    ///
+    pub fn panel(&self) -> &Panel {
+        self.display.panel()
+    }
+
+    ///
+    /// Turns the backlight on or off, see `ST7789::set_backlight`
+    ///
+    pub fn set_backlight(
+        &mut self,
+        state: BacklightState,
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_backlight(state, delay_source)
+    }
+
+    ///
+    /// Returns the last set backlight brightness in percent
+    ///
+    pub fn brightness(&self) -> u8 {
+        self.display.brightness()
+    }
+
+    ///
+    /// Sets the backlight brightness, see `ST7789::set_brightness`
+    ///
+    pub fn set_brightness(&mut self, percent: u8) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_brightness(percent)
+    }
+
+    ///
+    /// Fades the backlight to the given brightness, see `ST7789::fade_brightness`
+    ///
+    pub fn fade_brightness(
+        &mut self,
+        percent: u8,
+        duration_us: u32,
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Result<(), Error<RstE, BlE>> {
+        self.display
+            .fade_brightness(percent, duration_us, delay_source)
+    }
+
+    // Runs a state changing operation, keeping the current state if it fails.
+    fn transition<T, F>(mut self, f: F) -> Transition<Driver<DI, RST, BL, C, T>, Self, RstE, BlE>
+    where
+        F: FnOnce(&mut ST7789<DI, RST, BL, C>) -> Result<(), Error<RstE, BlE>>,
+    {
+        match f(&mut self.display) {
+            Ok(()) => Ok(Driver {
+                display: self.display,
+                _state: PhantomData,
+            }),
+            Err(error) => Err((self, error)),
+        }
+    }
+}
+
+impl<DI, RST, BL, C, RstE, BlE> Driver<DI, RST, BL, C, Uninitialized>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+{
+    ///
+    /// Creates a new uninitialized driver instance, see `ST7789::new`
+    ///
+    /// # Arguments
+    ///
+    /// * `di` - a display interface for talking with the display
+    /// * `rst` - display hard reset pin, `NoPin` if not connected
+    /// * `bl` - backlight, `NoPin` if not connected
+    /// * `panel` - geometry of the attached panel
+    ///
+    pub fn new(di: DI, rst: RST, bl: BL, panel: Panel) -> Self {
+        Self {
+            display: ST7789::new(di, rst, bl, panel),
+            _state: PhantomData,
+        }
+    }
+
+    ///
+    /// Initializes the display using the settings of the panel, see `ST7789::init`
+    ///
+    pub fn init(
+        self,
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Transition<Driver<DI, RST, BL, C, Ready>, Self, RstE, BlE> {
+        self.transition(|display| display.init(delay_source))
+    }
+
+    ///
+    /// Initializes the display with the given configuration, see `ST7789::init_with`
+    ///
+    pub fn init_with(
+        self,
+        config: &InitConfig,
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Transition<Driver<DI, RST, BL, C, Ready>, Self, RstE, BlE> {
+        self.transition(|display| display.init_with(config, delay_source))
+    }
+}
+
+impl<DI, RST, BL, C, RstE, BlE> Driver<DI, RST, BL, C, Ready>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+{
+    ///
+    /// Enters sleep mode, see `ST7789::sleep`
+    ///
+    pub fn sleep(
+        self,
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Transition<Driver<DI, RST, BL, C, Sleeping>, Self, RstE, BlE> {
+        self.transition(|display| display.sleep(delay_source))
+    }
+
+    ///
+    /// Returns currently set orientation, see `ST7789::orientation`
+    ///
+    pub fn orientation(&self) -> Option<Orientation> {
+        self.display.orientation()
+    }
+
+    ///
+    /// Sets display orientation
+    ///
+    pub fn set_orientation(&mut self, orientation: Orientation) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_orientation(orientation)
+    }
+
+    ///
+    /// Returns the current memory access control
+    ///
+    pub fn memory_access_control(&self) -> MemoryAccessControl {
+        self.display.memory_access_control()
+    }
+
+    ///
+    /// Sets the memory access control, see `ST7789::set_memory_access_control`
+    ///
+    pub fn set_memory_access_control(
+        &mut self,
+        madctl: MemoryAccessControl,
+    ) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_memory_access_control(madctl)
+    }
+
+    ///
+    /// Returns the current interface pixel format
+    ///
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.display.pixel_format()
+    }
+
+    ///
+    /// Sets the interface pixel format, see `ST7789::set_pixel_format`
+    ///
+    pub fn set_pixel_format(&mut self, pixel_format: PixelFormat) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_pixel_format(pixel_format)
+    }
+
+    ///
+    /// Sets a pixel color at the given coords, see `ST7789::set_pixel`
+    ///
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: C) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_pixel(x, y, color)
+    }
+
+    ///
+    /// Sets pixel colors in given rectangle bounds, see `ST7789::set_pixels`
+    ///
+    pub fn set_pixels<T>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        colors: T,
+    ) -> Result<(), Error<RstE, BlE>>
+    where
+        T: IntoIterator<Item = C>,
+    {
+        self.display.set_pixels(sx, sy, ex, ey, colors)
+    }
+
+    ///
+    /// Continues writing pixel colors, see `ST7789::set_pixels_continue`
+    ///
+    pub fn set_pixels_continue<T>(&mut self, colors: T) -> Result<(), Error<RstE, BlE>>
+    where
+        T: IntoIterator<Item = C>,
+    {
+        self.display.set_pixels_continue(colors)
+    }
+
+    ///
+    /// Returns the screen axis moved by hardware scrolling, see `ST7789::scroll_axis`
+    ///
+    pub fn scroll_axis(&self) -> ScrollAxis {
+        self.display.scroll_axis()
+    }
+
+    ///
+    /// Returns the current scroll offset relative to the scroll area
+    ///
+    pub fn scroll_offset(&self) -> u16 {
+        self.display.scroll_offset()
+    }
+
+    ///
+    /// Returns the current scroll offset in screen direction
+    ///
+    pub fn screen_scroll_offset(&self) -> u16 {
+        self.display.screen_scroll_offset()
+    }
+
+    ///
+    /// Maps screen coordinates to the coordinates drawn at them, see `ST7789::map_scrolled`
+    ///
+    pub fn map_scrolled(&self, x: u16, y: u16) -> Option<(u16, u16)> {
+        self.display.map_scrolled(x, y)
+    }
+
+    ///
+    /// Sets scroll offset "shifting" the displayed picture, see `ST7789::set_scroll_offset`
+    ///
+    pub fn set_scroll_offset(&mut self, offset: u16) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_scroll_offset(offset)
+    }
+
+    ///
+    /// Returns the current scroll area
+    ///
+    pub fn scroll_area(&self) -> ScrollArea {
+        self.display.scroll_area()
+    }
+
+    ///
+    /// Sets the scroll area, see `ST7789::set_scroll_area`
+    ///
+    pub fn set_scroll_area(&mut self, area: ScrollArea) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_scroll_area(area)
+    }
+
+    ///
+    /// Sets the scroll offset in screen direction, see `ST7789::set_screen_scroll_offset`
+    ///
+    pub fn set_screen_scroll_offset(&mut self, offset: u16) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_screen_scroll_offset(offset)
+    }
+
+    ///
+    /// Turns display inversion on or off
+    ///
+    pub fn set_inversion(&mut self, inverted: bool) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_inversion(inverted)
+    }
+
+    ///
+    /// Configures the tearing effect output
+    ///
+    pub fn set_tearing_effect(
+        &mut self,
+        tearing_effect: TearingEffect,
+    ) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_tearing_effect(tearing_effect)
+    }
+
+    ///
+    /// Sets the scanline the tearing effect signal is output at
+    ///
+    pub fn set_tearing_scanline(&mut self, line: u16) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_tearing_scanline(line)
+    }
+
+    ///
+    /// Selects one of the predefined gamma curves
+    ///
+    pub fn set_gamma_preset(&mut self, preset: GammaPreset) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_gamma_preset(preset)
+    }
+
+    ///
+    /// Writes custom positive and negative voltage gamma tables
+    ///
+    pub fn set_gamma_curve(&mut self, curve: &GammaCurve) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_gamma_curve(curve)
+    }
+
+    ///
+    /// Sets the frame rate in normal mode, see `ST7789::set_frame_rate`
+    ///
+    pub fn set_frame_rate(&mut self, frame_rate: FrameRate) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_frame_rate(frame_rate)
+    }
+
+    ///
+    /// Sets the porch settings, see `ST7789::set_porch`
+    ///
+    pub fn set_porch(&mut self, porch: &PorchConfig) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_porch(porch)
+    }
+
+    ///
+    /// Writes the power control registers, see `ST7789::set_power_config`
+    ///
+    pub fn set_power_config(&mut self, config: &PanelPowerConfig) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_power_config(config)
+    }
+
+    ///
+    /// Sends an instruction followed by its raw parameter bytes, see `ST7789::write_instruction`.
+    /// Sleep instructions bypass the lifecycle tracking, use `sleep` instead.
+    ///
+    pub fn write_instruction(
+        &mut self,
+        instruction: Instruction,
+        params: &[u8],
+    ) -> Result<(), Error<RstE, BlE>> {
+        self.display.write_instruction(instruction, params)
+    }
+
+    ///
+    /// Executes an init command table, see `ST7789::run_init_table`.
+    /// Sleep commands bypass the lifecycle tracking, use `sleep` instead.
+    ///
+    pub fn run_init_table(
+        &mut self,
+        table: &[InitCommand],
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Result<(), Error<RstE, BlE>> {
+        self.display.run_init_table(table, delay_source)
+    }
+
+    ///
+    /// Returns the current power state, `DisplayOn` or `DisplayOff` while ready
+    ///
+    pub fn power_state(&self) -> PowerState {
+        self.display.power_state()
+    }
+
+    ///
+    /// Turns the display output on or off, see `ST7789::set_display_on`
+    ///
+    pub fn set_display_on(&mut self, on: bool) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_display_on(on)
+    }
+
+    ///
+    /// Returns true if idle mode is on
+    ///
+    pub fn is_idle_mode(&self) -> bool {
+        self.display.is_idle_mode()
+    }
+
+    ///
+    /// Turns idle mode on or off, see `ST7789::set_idle_mode`
+    ///
+    pub fn set_idle_mode(&mut self, idle: bool) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_idle_mode(idle)
+    }
+
+    ///
+    /// Sets the rows shown in partial mode, see `ST7789::set_partial_area`
+    ///
+    pub fn set_partial_area(&mut self, start: u16, end: u16) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_partial_area(start, end)
+    }
+
+    ///
+    /// Returns true if partial mode is on
+    ///
+    pub fn is_partial_mode(&self) -> bool {
+        self.display.is_partial_mode()
+    }
+
+    ///
+    /// Shows only the partial area, see `ST7789::enter_partial_mode`
+    ///
+    pub fn enter_partial_mode(&mut self) -> Result<(), Error<RstE, BlE>> {
+        self.display.enter_partial_mode()
+    }
+
+    ///
+    /// Returns to normal mode showing the whole display
+    ///
+    pub fn exit_partial_mode(&mut self) -> Result<(), Error<RstE, BlE>> {
+        self.display.exit_partial_mode()
+    }
+
+    ///
+    /// Sets the display brightness, see `ST7789::set_display_brightness`
+    ///
+    pub fn set_display_brightness(&mut self, brightness: u8) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_display_brightness(brightness)
+    }
+
+    ///
+    /// Configures the brightness control block, see `ST7789::set_brightness_control`
+    ///
+    pub fn set_brightness_control(
+        &mut self,
+        control: BrightnessControl,
+    ) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_brightness_control(control)
+    }
+
+    ///
+    /// Sets the content adaptive brightness control mode
+    ///
+    pub fn set_adaptive_brightness(&mut self, mode: CabcMode) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_adaptive_brightness(mode)
+    }
+
+    ///
+    /// Sets the minimum content adaptive brightness, see `ST7789::set_cabc_min_brightness`
+    ///
+    pub fn set_cabc_min_brightness(&mut self, brightness: u8) -> Result<(), Error<RstE, BlE>> {
+        self.display.set_cabc_min_brightness(brightness)
+    }
+
+    ///
+    /// Returns the dynamic driver, giving up the lifecycle tracking
+    ///
+    pub fn into_dynamic(self) -> ST7789<DI, RST, BL, C> {
+        self.display
+    }
+}
+
+impl<DI, RST, BL, C> Borrow<DI, RST, BL, C> for Driver<DI, RST, BL, C, Ready>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin,
+    BL: Backlight,
+    C: RgbColor,
+{
+    fn driver(&mut self, _token: Token) -> &mut ST7789<DI, RST, BL, C> {
+        &mut self.display
+    }
+}
+
+impl<DI, RST, BL, C, RstE, BlE> Driver<DI, RST, BL, C, Sleeping>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+{
+    ///
+    /// Leaves sleep mode, see `ST7789::wake`
+    ///
+    pub fn wake(
+        self,
+        delay_source: &mut impl DelayUs<u32>,
+    ) -> Transition<Driver<DI, RST, BL, C, Ready>, Self, RstE, BlE> {
+        self.transition(|display| display.wake(delay_source))
+    }
+}
+
+impl<DI, RST, BL, C, S, RstE, BlE> Driver<DI, RST, BL, C, S>
+where
+    DI: WriteOnlyDataCommand + ReadDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+{
+    ///
+    /// Reads the display identification
+    ///
+    pub fn read_id(&mut self) -> Result<DisplayId, Error<RstE, BlE>> {
+        self.display.read_id()
+    }
+
+    ///
+    /// Reads and decodes the display status
+    ///
+    pub fn read_status(&mut self) -> Result<DisplayStatus, Error<RstE, BlE>> {
+        self.display.read_status()
+    }
+
+    ///
+    /// Reads and decodes the display power mode
+    ///
+    pub fn read_power_mode(&mut self) -> Result<DisplayPowerMode, Error<RstE, BlE>> {
+        self.display.read_power_mode()
+    }
+
+    ///
+    /// Reads the memory access control register
+    ///
+    pub fn read_memory_access_control(&mut self) -> Result<MemoryAccessControl, Error<RstE, BlE>> {
+        self.display.read_memory_access_control()
+    }
+
+    ///
+    /// Reads the interface pixel format, see `ST7789::read_pixel_format`
+    ///
+    pub fn read_pixel_format(&mut self) -> Result<Option<PixelFormat>, Error<RstE, BlE>> {
+        self.display.read_pixel_format()
+    }
+
+    ///
+    /// Reads and decodes the display image mode
+    ///
+    pub fn read_image_mode(&mut self) -> Result<DisplayImageMode, Error<RstE, BlE>> {
+        self.display.read_image_mode()
+    }
+
+    ///
+    /// Reads and decodes the display signal mode
+    ///
+    pub fn read_signal_mode(&mut self) -> Result<DisplaySignalMode, Error<RstE, BlE>> {
+        self.display.read_signal_mode()
+    }
+
+    ///
+    /// Reads the self-diagnostic result of the last sleep out
+    ///
+    pub fn read_self_diagnostic(&mut self) -> Result<SelfDiagnostic, Error<RstE, BlE>> {
+        self.display.read_self_diagnostic()
+    }
+
+    ///
+    /// Reads the display brightness
+    ///
+    pub fn read_display_brightness(&mut self) -> Result<u8, Error<RstE, BlE>> {
+        self.display.read_display_brightness()
+    }
+
+    ///
+    /// Reads the brightness control settings
+    ///
+    pub fn read_brightness_control(&mut self) -> Result<BrightnessControl, Error<RstE, BlE>> {
+        self.display.read_brightness_control()
+    }
+
+    ///
+    /// Reads the content adaptive brightness control mode
+    ///
+    pub fn read_adaptive_brightness(&mut self) -> Result<CabcMode, Error<RstE, BlE>> {
+        self.display.read_adaptive_brightness()
+    }
+
+    ///
+    /// Reads the CABC minimum brightness
+    ///
+    pub fn read_cabc_min_brightness(&mut self) -> Result<u8, Error<RstE, BlE>> {
+        self.display.read_cabc_min_brightness()
+    }
+
+    ///
+    /// Reads the display identification through the RDID1, RDID2 and RDID3 registers
+    ///
+    pub fn read_id_registers(&mut self) -> Result<DisplayId, Error<RstE, BlE>> {
+        self.display.read_id_registers()
+    }
+
+    ///
+    /// Checks that an ST7789 is attached and responding, see `ST7789::verify`
+    ///
+    pub fn verify(
+        &mut self,
+        expected: Option<DisplayId>,
+    ) -> Result<DisplayStatus, Error<RstE, BlE>> {
+        self.display.verify(expected)
+    }
+
+    ///
+    /// Reads pixel colors in given rectangle bounds, see `ST7789::read_pixels`
+    ///
+    pub fn read_pixels<P>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        pixels: &mut [P],
+    ) -> Result<(), Error<RstE, BlE>>
+    where
+        P: ReadColor,
+    {
+        self.display.read_pixels(sx, sy, ex, ey, pixels)
+    }
+
+    ///
+    /// Streams pixel colors in given rectangle bounds, see `ST7789::read_pixels_with`
+    ///
+    pub fn read_pixels_with<P, F>(
+        &mut self,
+        sx: u16,
+        sy: u16,
+        ex: u16,
+        ey: u16,
+        f: F,
+    ) -> Result<(), Error<RstE, BlE>>
+    where
+        P: ReadColor,
+        F: FnMut(P),
+    {
+        self.display.read_pixels_with(sx, sy, ex, ey, f)
+    }
+
+    ///
+    /// Streams the display RAM of the visible area, see `ST7789::screenshot`
+    ///
+    pub fn screenshot<P, F>(&mut self, f: F) -> Result<(), Error<RstE, BlE>>
+    where
+        P: ReadColor,
+        F: FnMut(P),
+    {
+        self.display.screenshot(f)
+    }
+}
+
+#[cfg(feature = "graphics")]
+mod graphics {
+    use display_interface::WriteOnlyDataCommand;
+    use embedded_graphics_core::draw_target::DrawTarget;
+    use embedded_graphics_core::geometry::{OriginDimensions, Size};
+    use embedded_graphics_core::pixelcolor::RgbColor;
+    use embedded_graphics_core::primitives::Rectangle;
+    use embedded_graphics_core::Pixel;
+
+    use super::{Driver, Ready};
+    use crate::{Backlight, Error, ResetPin};
+
+    impl<DI, RST, BL, C, RstE, BlE> DrawTarget for Driver<DI, RST, BL, C, Ready>
+    where
+        DI: WriteOnlyDataCommand,
+        RST: ResetPin<Error = RstE>,
+        BL: Backlight<Error = BlE>,
+        C: RgbColor,
+    {
+        type Error = Error<RstE, BlE>;
+        type Color = C;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            self.display.draw_iter(pixels)
+        }
+
+        fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Self::Color>,
+        {
+            self.display.fill_contiguous(area, colors)
+        }
+
+        fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+            self.display.fill_solid(area, color)
+        }
+
+        fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+            self.display.clear(color)
+        }
+    }
+
+    impl<DI, RST, BL, C, RstE, BlE> OriginDimensions for Driver<DI, RST, BL, C, Ready>
+    where
+        DI: WriteOnlyDataCommand,
+        RST: ResetPin<Error = RstE>,
+        BL: Backlight<Error = BlE>,
+        C: RgbColor,
+    {
+        fn size(&self) -> Size {
+            self.display.size()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockInterface, NoDelay};
+    use crate::{NoPin, Terminal};
+    use embedded_graphics_core::pixelcolor::Rgb565;
+
+    fn ready() -> Driver<MockInterface, NoPin, NoPin, Rgb565, Ready> {
+        Driver::new(MockInterface::default(), NoPin, NoPin, Panel::default())
+            .init(&mut NoDelay)
+            .ok()
+            .unwrap()
+    }
+
+    #[test]
+    fn terminal_borrows_ready_driver() {
+        let mut driver = ready();
+        let mut terminal =
+            Terminal::new(&mut driver, 10, 10, Rgb565::WHITE, Rgb565::BLACK).unwrap();
+        terminal.write_char('A').unwrap();
+        terminal.display().set_inversion(true).unwrap();
+
+        assert_eq!(driver.scroll_area(), ScrollArea::new(10, 296, 14));
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn window_borrows_ready_driver() {
+        use crate::Window;
+        use embedded_graphics_core::draw_target::DrawTarget;
+        use embedded_graphics_core::prelude::{Point, Size};
+        use embedded_graphics_core::primitives::Rectangle;
+
+        let mut driver = ready();
+        let area = Rectangle::new(Point::new(10, 10), Size::new(20, 20));
+        let mut window = Window::new(&mut driver, area);
+        window.clear(Rgb565::RED).unwrap();
+
+        assert_eq!(window.area(), area);
+    }
+}
@@ -0,0 +1,135 @@
+//! Clipped sub-window draw targets.
+
+use core::marker::PhantomData;
+
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics_core::draw_target::DrawTarget;
+use embedded_graphics_core::geometry::{OriginDimensions, Size};
+use embedded_graphics_core::pixelcolor::RgbColor;
+use embedded_graphics_core::primitives::Rectangle;
+use embedded_graphics_core::Pixel;
+
+use crate::private::Token;
+use crate::{Backlight, DisplayDriver, Error, ResetPin, ST7789};
+
+///
+/// Draw target covering a rectangle of the display.
+///
+/// Coordinates are relative to the top left corner of the rectangle and
+/// drawing is clipped to the rectangle and the visible area of the display.
+/// The window borrows either an `ST7789` or a ready `typestate::Driver`, see `DisplayDriver`.
+///
+pub struct Window<'a, DI, RST, BL, C, D = ST7789<DI, RST, BL, C>>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin,
+    BL: Backlight,
+    C: RgbColor,
+    D: DisplayDriver<DI, RST, BL, C>,
+{
+    display: &'a mut D,
+    area: Rectangle,
+
+    _phantom: PhantomData<(DI, RST, BL, C)>,
+}
+
+impl<'a, DI, RST, BL, C, D, RstE, BlE> Window<'a, DI, RST, BL, C, D>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+    D: DisplayDriver<DI, RST, BL, C>,
+{
+    ///
+    /// Creates a window over a rectangle of the display
+    ///
+    /// # Arguments
+    ///
+    /// * `display` - the display to draw to
+    /// * `area` - the rectangle in display coordinates
+    ///
+    pub fn new(display: &'a mut D, area: Rectangle) -> Self {
+        Self {
+            display,
+            area,
+            _phantom: PhantomData,
+        }
+    }
+
+    ///
+    /// Returns the rectangle covered by this window in display coordinates
+    ///
+    pub fn area(&self) -> Rectangle {
+        self.area
+    }
+
+    ///
+    /// Returns the display this window draws to
+    ///
+    pub fn display(&mut self) -> &mut D {
+        self.display
+    }
+
+    // Converts a rectangle from window to display coordinates.
+    fn to_display(&self, area: &Rectangle) -> Rectangle {
+        Rectangle::new(area.top_left + self.area.top_left, area.size)
+    }
+}
+
+impl<'a, DI, RST, BL, C, D, RstE, BlE> DrawTarget for Window<'a, DI, RST, BL, C, D>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+    D: DisplayDriver<DI, RST, BL, C>,
+{
+    type Error = Error<RstE, BlE>;
+    type Color = C;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let area = self.area;
+        let pixels = pixels
+            .into_iter()
+            .map(|Pixel(point, color)| Pixel(point + area.top_left, color))
+            .filter(|Pixel(point, _)| area.contains(*point));
+
+        self.display.driver(Token(())).draw_iter(pixels)
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let area = self.to_display(area);
+        self.display
+            .driver(Token(()))
+            .fill_contiguous_clipped(&area, &self.area, colors)
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let area = self.to_display(area).intersection(&self.area);
+        self.display.driver(Token(())).fill_solid(&area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.display.driver(Token(())).fill_solid(&self.area, color)
+    }
+}
+
+impl<'a, DI, RST, BL, C, D, RstE, BlE> OriginDimensions for Window<'a, DI, RST, BL, C, D>
+where
+    DI: WriteOnlyDataCommand,
+    RST: ResetPin<Error = RstE>,
+    BL: Backlight<Error = BlE>,
+    C: RgbColor,
+    D: DisplayDriver<DI, RST, BL, C>,
+{
+    fn size(&self) -> Size {
+        self.area.size
+    }
+}